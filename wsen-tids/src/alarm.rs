@@ -0,0 +1,381 @@
+//! Over/under-temperature alarm monitor driven by the interrupt pin.
+
+use embedded_hal::{
+    digital::{Error as _, InputPin},
+    i2c::{ErrorType, I2c},
+};
+
+use crate::{Error, Sensor, TEMPERATURE_LIMIT_MAX_CENTI, TEMPERATURE_LIMIT_MIN_CENTI};
+
+/// Alarm event
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmEvent {
+    /// The temperature rose above the high limit.
+    Overheat,
+    /// The temperature dropped below the low limit.
+    Undercool,
+    /// The temperature returned inside the limits by at least the hysteresis.
+    Cleared,
+}
+
+/// Alarm state
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AlarmState {
+    Normal,
+    Overheat,
+    Undercool,
+}
+
+/// Sensor and interrupt pin with the error of a failed [`AlarmMonitor::new`]
+pub type MonitorError<I2C, INT> = (Sensor<I2C>, INT, Error<<I2C as ErrorType>::Error>);
+
+/// Monitors a sensor for limit crossings.
+///
+/// While the temperature is within the limits, both limit registers are
+/// armed. Once a limit has tripped, the opposite limit register is set to the
+/// tripped limit moved back by the hysteresis, so that
+/// [`AlarmEvent::Cleared`] is reported once the temperature has recovered.
+///
+/// The sensor must be configured for continuous conversion for the limits to
+/// be evaluated.
+#[derive(Debug)]
+pub struct AlarmMonitor<I2C, INT> {
+    sensor: Sensor<I2C>,
+    int: INT,
+    high_centi: i32,
+    low_centi: i32,
+    hysteresis_centi: i32,
+    state: AlarmState,
+    // the limit registers match the state
+    armed: bool,
+}
+
+impl<I2C: I2c, INT: InputPin> AlarmMonitor<I2C, INT> {
+    /// Creates a new alarm monitor and arms the sensor limits.
+    ///
+    /// All temperatures are in hundredths of degrees celcius. Fails with
+    /// [`Error::LimitOutOfRange`] unless both limits are within
+    /// [`TEMPERATURE_LIMIT_MIN_CENTI`] and [`TEMPERATURE_LIMIT_MAX_CENTI`] and
+    /// the hysteresis fits between them.
+    ///
+    /// Returns the sensor and interrupt pin together with the error on
+    /// failure.
+    pub fn new(
+        sensor: Sensor<I2C>,
+        int: INT,
+        high_centi: i32,
+        low_centi: i32,
+        hysteresis_centi: i32,
+    ) -> Result<Self, MonitorError<I2C, INT>> {
+        let limits = TEMPERATURE_LIMIT_MIN_CENTI..=TEMPERATURE_LIMIT_MAX_CENTI;
+
+        // with both limits in range, the re-armed limits are in range as well
+        let valid = limits.contains(&high_centi)
+            && limits.contains(&low_centi)
+            && hysteresis_centi >= 0
+            && matches!(high_centi.checked_sub(hysteresis_centi), Some(cleared) if cleared > low_centi);
+
+        if !valid {
+            return Err((sensor, int, Error::LimitOutOfRange));
+        }
+
+        let mut monitor = Self {
+            sensor,
+            int,
+            high_centi,
+            low_centi,
+            hysteresis_centi,
+            state: AlarmState::Normal,
+            armed: false,
+        };
+
+        match monitor.arm() {
+            Ok(()) => Ok(monitor),
+            Err(e) => Err((monitor.sensor, monitor.int, e)),
+        }
+    }
+
+    /// Current alarm state.
+    pub fn state(&self) -> AlarmState {
+        self.state
+    }
+
+    /// Check the interrupt pin and report any alarm event.
+    ///
+    /// The status register is only read while the (active low) interrupt pin
+    /// is asserted. The limits are re-armed for the new state on every event.
+    ///
+    /// Reading the status register clears the limit flags, so an event is
+    /// reported even if re-arming the limits fails. The limits are then
+    /// re-armed by the next call, which returns the error if that fails again.
+    pub fn poll(&mut self) -> Result<Option<AlarmEvent>, Error<I2C::Error>> {
+        if !self.armed {
+            self.arm()?;
+        }
+
+        if self.int.is_high().map_err(|e| Error::Pin(e.kind()))? {
+            return Ok(None);
+        }
+
+        let status = self.sensor.read_status()?;
+
+        let event = match self.state {
+            AlarmState::Normal if status.over_high_limit => AlarmEvent::Overheat,
+            AlarmState::Normal if status.under_low_limit => AlarmEvent::Undercool,
+            AlarmState::Overheat if status.under_low_limit => AlarmEvent::Cleared,
+            AlarmState::Undercool if status.over_high_limit => AlarmEvent::Cleared,
+            _ => return Ok(None),
+        };
+
+        self.state = match event {
+            AlarmEvent::Overheat => AlarmState::Overheat,
+            AlarmEvent::Undercool => AlarmState::Undercool,
+            AlarmEvent::Cleared => AlarmState::Normal,
+        };
+        self.armed = false;
+
+        // retried by the next poll on failure
+        let _ = self.arm();
+
+        Ok(Some(event))
+    }
+
+    /// Monitored sensor.
+    ///
+    /// Changing the limit registers through it disturbs the alarm state.
+    pub fn sensor(&mut self) -> &mut Sensor<I2C> {
+        &mut self.sensor
+    }
+
+    /// Releases the sensor and interrupt pin.
+    pub fn release(self) -> (Sensor<I2C>, INT) {
+        (self.sensor, self.int)
+    }
+
+    /// Program the limit registers for the current state.
+    fn arm(&mut self) -> Result<(), Error<I2C::Error>> {
+        match self.state {
+            AlarmState::Normal => {
+                self.sensor.temperature_high_limit_centi(self.high_centi)?;
+                self.sensor.temperature_low_limit_centi(self.low_centi)?;
+            }
+            AlarmState::Overheat => {
+                self.sensor.disable_temperature_high_limit()?;
+                self.sensor
+                    .temperature_low_limit_centi(self.high_centi - self.hysteresis_centi)?;
+            }
+            AlarmState::Undercool => {
+                self.sensor
+                    .temperature_high_limit_centi(self.low_centi + self.hysteresis_centi)?;
+                self.sensor.disable_temperature_low_limit()?;
+            }
+        }
+
+        self.armed = true;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        registers::{Register, Status, TempHighLimit, TempL, TempLowLimit},
+        AddressSelect,
+    };
+    use embedded_hal::{digital::ErrorKind, i2c::ErrorKind as I2cErrorKind};
+    use embedded_hal_mock::eh1::{
+        digital::{Mock as PinMock, State as PinState, Transaction as PinTransaction},
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+        MockError,
+    };
+    use std::{io, vec};
+
+    /// Transactions arming the limits at 40 °C and 0 °C.
+    fn armed() -> [I2cTransaction; 2] {
+        [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63]),
+        ]
+    }
+
+    #[test]
+    fn test_events() {
+        let mut expectations = vec![];
+        expectations.extend(armed());
+        expectations.extend([
+            // overheat, cleared at 35 °C
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b010]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 117]),
+            // spurious interrupt
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b100]),
+        ]);
+        expectations.extend(armed());
+        expectations.extend([
+            // undercool, cleared at 5 °C
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b100]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 70]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b010]),
+        ]);
+        expectations.extend(armed());
+        let int = PinMock::new(&[
+            PinTransaction::get(PinState::High),
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::Low),
+        ]);
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let mut monitor = AlarmMonitor::new(sensor, int, 4000, 0, 500).unwrap();
+        assert_eq!(monitor.state(), AlarmState::Normal);
+
+        // the status is only read while the interrupt is asserted
+        assert_eq!(monitor.poll().unwrap(), None);
+
+        assert_eq!(monitor.poll().unwrap(), Some(AlarmEvent::Overheat));
+        assert_eq!(monitor.state(), AlarmState::Overheat);
+        assert_eq!(monitor.poll().unwrap(), None);
+        assert_eq!(monitor.poll().unwrap(), Some(AlarmEvent::Cleared));
+        assert_eq!(monitor.state(), AlarmState::Normal);
+
+        assert_eq!(monitor.poll().unwrap(), Some(AlarmEvent::Undercool));
+        assert_eq!(monitor.state(), AlarmState::Undercool);
+        assert_eq!(monitor.poll().unwrap(), Some(AlarmEvent::Cleared));
+        assert_eq!(monitor.state(), AlarmState::Normal);
+
+        let (sensor, mut int) = monitor.release();
+        sensor.release().done();
+        int.done();
+    }
+
+    #[test]
+    fn test_pin_error() {
+        let expectations = armed();
+        let int = PinMock::new(&[PinTransaction::get(PinState::Low)
+            .with_error(MockError::Io(io::ErrorKind::NotConnected))]);
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let mut monitor = AlarmMonitor::new(sensor, int, 4000, 0, 500).unwrap();
+        assert_eq!(monitor.poll(), Err(Error::Pin(ErrorKind::Other)));
+        assert_eq!(monitor.state(), AlarmState::Normal);
+
+        let (sensor, mut int) = monitor.release();
+        sensor.release().done();
+        int.done();
+    }
+
+    #[test]
+    fn test_sensor() {
+        let mut expectations = vec![];
+        expectations.extend(armed());
+        expectations.push(I2cTransaction::write_read(
+            0x38,
+            vec![TempL::ADDRESS],
+            vec![0xC4, 0x09],
+        ));
+        let int = PinMock::new(&[]);
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let mut monitor = AlarmMonitor::new(sensor, int, 4000, 0, 500).unwrap();
+        assert_eq!(monitor.sensor().read_temperature_centi().unwrap(), 2500);
+
+        let (sensor, mut int) = monitor.release();
+        sensor.release().done();
+        int.done();
+    }
+
+    #[test]
+    fn test_limit_out_of_range() {
+        let limits = [
+            (TEMPERATURE_LIMIT_MAX_CENTI + 1, 0, 500),
+            (4000, TEMPERATURE_LIMIT_MIN_CENTI - 1, 500),
+            (i32::MAX, 0, 500),
+            (4000, i32::MIN, 500),
+            (
+                TEMPERATURE_LIMIT_MIN_CENTI,
+                TEMPERATURE_LIMIT_MIN_CENTI,
+                i32::MAX,
+            ),
+            (4000, 0, -1),
+            // hysteresis does not fit between the limits
+            (4000, 0, 4000),
+        ];
+
+        for (high, low, hysteresis) in limits {
+            let sensor = Sensor::new(I2cMock::new(&[]), AddressSelect::High);
+            let int = PinMock::new(&[]);
+
+            // nothing is written and both are handed back
+            let (sensor, mut int, error) =
+                AlarmMonitor::new(sensor, int, high, low, hysteresis).unwrap_err();
+            assert_eq!(error, Error::LimitOutOfRange);
+
+            sensor.release().done();
+            int.done();
+        }
+    }
+
+    #[test]
+    fn test_arm_failure() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125])
+                .with_error(I2cErrorKind::Other),
+        ];
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+        let int = PinMock::new(&[]);
+
+        let (sensor, mut int, error) = AlarmMonitor::new(sensor, int, 4000, 0, 500).unwrap_err();
+        assert_eq!(error, Error::I2c(I2cErrorKind::Other));
+
+        sensor.release().done();
+        int.done();
+    }
+
+    #[test]
+    fn test_rearm_failure() {
+        let mut expectations = vec![];
+        expectations.extend(armed());
+        expectations.extend([
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b010]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0])
+                .with_error(I2cErrorKind::ArbitrationLoss),
+            // retried by the following polls
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0])
+                .with_error(I2cErrorKind::ArbitrationLoss),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 117]),
+        ]);
+        let int = PinMock::new(&[
+            PinTransaction::get(PinState::Low),
+            PinTransaction::get(PinState::High),
+        ]);
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let mut monitor = AlarmMonitor::new(sensor, int, 4000, 0, 500).unwrap();
+
+        // the event is not lost with the cleared status flags
+        assert_eq!(monitor.poll().unwrap(), Some(AlarmEvent::Overheat));
+        assert_eq!(monitor.state(), AlarmState::Overheat);
+
+        assert_eq!(
+            monitor.poll(),
+            Err(Error::I2c(I2cErrorKind::ArbitrationLoss))
+        );
+        assert_eq!(monitor.poll().unwrap(), None);
+        assert_eq!(monitor.state(), AlarmState::Overheat);
+
+        let (sensor, mut int) = monitor.release();
+        sensor.release().done();
+        int.done();
+    }
+}
@@ -0,0 +1,200 @@
+//! Several sensors operated together.
+//!
+//! A [`TidsArray`] owns a fixed number of sensors, e.g. at both addresses on
+//! one or more buses shared through `embedded-hal-bus`. All sensors are
+//! configured identically and measured with synchronized single conversions.
+//! Each operation returns one result per sensor, so a failing sensor does not
+//! prevent reading the others.
+//!
+//! All sensors share the same bus type. Sensors on buses of different types,
+//! e.g. on two different I²C peripherals, can be combined as
+//! `TidsArray<&mut dyn I2c<Error = E>, N>`, as long as the buses have the same
+//! error type `E`.
+
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+
+use crate::{Averaging, ConversionPoll, Error, Mode, Sensor};
+
+/// Array of `N` sensors
+#[derive(Debug)]
+pub struct TidsArray<I2C, const N: usize> {
+    sensors: [Sensor<I2C>; N],
+}
+
+impl<I2C: I2c, const N: usize> TidsArray<I2C, N> {
+    /// Creates a new sensor array.
+    pub fn new(sensors: [Sensor<I2C>; N]) -> Self {
+        Self { sensors }
+    }
+
+    /// Releases the sensors.
+    pub fn release(self) -> [Sensor<I2C>; N] {
+        self.sensors
+    }
+
+    /// Sensor at the given index.
+    pub fn sensor(&mut self, index: usize) -> Option<&mut Sensor<I2C>> {
+        self.sensors.get_mut(index)
+    }
+
+    /// Configure the operating mode of all sensors.
+    pub fn configure(&mut self, mode: Mode) -> [Result<(), Error<I2C::Error>>; N] {
+        core::array::from_fn(|i| self.sensors[i].configure(mode))
+    }
+
+    /// Set the averaging of all sensors.
+    ///
+    /// See [`Sensor::set_averaging`].
+    pub fn set_averaging(&mut self, averaging: Averaging) {
+        for sensor in &mut self.sensors {
+            sensor.set_averaging(averaging);
+        }
+    }
+
+    /// Take a single temperature measurement on all sensors.
+    ///
+    /// Conversions are triggered on all sensors first and then polled
+    /// together, so they run at the same time. A sensor whose conversion does
+    /// not complete within 100 ms is powered down and reports
+    /// [`Error::Timeout`].
+    ///
+    /// The results are in hundredths of degrees celcius.
+    pub fn measure_once_centi<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> [Result<i16, Error<I2C::Error>>; N] {
+        // `None` while the conversion is pending
+        let mut results: [Option<Result<i16, Error<I2C::Error>>>; N] = core::array::from_fn(|i| {
+            self.sensors[i]
+                .configure(Mode::SingleConversion)
+                .err()
+                .map(Err)
+        });
+
+        let mut poll = ConversionPoll::default();
+
+        while results.iter().any(Option::is_none) {
+            delay.delay_us(poll.interval_us());
+
+            for (sensor, result) in self.sensors.iter_mut().zip(results.iter_mut()) {
+                if result.is_some() {
+                    continue;
+                }
+
+                match sensor.is_busy() {
+                    Ok(false) => *result = Some(sensor.read_temperature_centi()),
+                    Ok(true) if poll.timed_out() => {
+                        *result = Some(sensor.configure(Mode::PowerDown).and(Err(Error::Timeout)))
+                    }
+                    Ok(true) => {}
+                    Err(e) => *result = Some(Err(e)),
+                }
+            }
+        }
+
+        results.map(|result| result.unwrap_or(Err(Error::Timeout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        registers::{Control, Register, Status, TempL},
+        AddressSelect,
+    };
+    use core::cell::RefCell;
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_bus::i2c::RefCellDevice;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+    use std::vec;
+
+    #[test]
+    fn test_measure_once() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write(0x3F, vec![Control::ADDRESS, 0b0100_1001]),
+            // first poll
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b001]),
+            I2cTransaction::write_read(0x3F, vec![Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x3F, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+            // second poll
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0])
+                .with_error(ErrorKind::Other),
+        ];
+        let bus = RefCell::new(I2cMock::new(&expectations));
+
+        let mut array = TidsArray::new([
+            Sensor::new(RefCellDevice::new(&bus), AddressSelect::High),
+            Sensor::new(RefCellDevice::new(&bus), AddressSelect::Low),
+        ]);
+
+        assert_eq!(
+            array.measure_once_centi(&mut NoopDelay),
+            [Err(Error::I2c(ErrorKind::Other)), Ok(-1000)]
+        );
+
+        array.release();
+        bus.into_inner().done();
+    }
+
+    #[test]
+    fn test_configure() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b1111_1000])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x3F, vec![Control::ADDRESS, 0b1111_1000]),
+        ];
+        let bus = RefCell::new(I2cMock::new(&expectations));
+
+        let mut array = TidsArray::new([
+            Sensor::new(RefCellDevice::new(&bus), AddressSelect::High),
+            Sensor::new(RefCellDevice::new(&bus), AddressSelect::Low),
+        ]);
+
+        array.set_averaging(Averaging::Min);
+        assert_eq!(
+            array.configure(Mode::LowOdr),
+            [Err(Error::I2c(ErrorKind::Other)), Ok(())]
+        );
+
+        array.release();
+        bus.into_inner().done();
+    }
+
+    #[test]
+    fn test_separate_buses() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+        ];
+        let mut i2c0 = I2cMock::new(&expectations);
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write_read(0x38, vec![Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+        ];
+        let mut i2c1 = I2cMock::new(&expectations);
+
+        // the same address on both buses
+        let mut array = TidsArray::<&mut dyn I2c<Error = ErrorKind>, 2>::new([
+            Sensor::new(&mut i2c0, AddressSelect::High),
+            Sensor::new(&mut i2c1, AddressSelect::High),
+        ]);
+
+        assert_eq!(
+            array.measure_once_centi(&mut NoopDelay),
+            [Ok(2500), Ok(-1000)]
+        );
+
+        array.release();
+        i2c0.done();
+        i2c1.done();
+    }
+}
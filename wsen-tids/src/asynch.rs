@@ -0,0 +1,669 @@
+//! Asynchronous driver built on `embedded-hal-async`.
+
+use embedded_hal::{digital::Error as _, i2c::SevenBitAddress};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, i2c::I2c};
+
+use crate::{
+    calibration::Calibration,
+    centi_temperature_to_reg_value,
+    diagnostics::Diagnostics,
+    raw_to_temperature, reg_value_to_centi_temperature, register_value,
+    registers::{
+        self, Control, DeviceId, Register, SoftReset, TempHighLimit, TempL, TempLowLimit, Writable,
+    },
+    should_retry, with_interface_bits, AddressSelect, Averaging, Config, ConversionPoll, Error,
+    Mode, Profile, Status, RESET_TIME_US,
+};
+#[cfg(feature = "float")]
+use crate::{centi_to_celcius, reg_value_to_temperature, temperature_to_reg_value};
+
+/// WSEN-TIDS temperature sensor on an asynchronous I²C bus
+///
+/// Mirrors [`crate::Sensor`], including the configuration restored after a
+/// reset. Additionally, [`Sensor::wait_for_alert`] awaits the interrupt pin
+/// instead of polling it.
+#[derive(Debug)]
+pub struct Sensor<I2C> {
+    i2c: I2C,
+    address: SevenBitAddress,
+    averaging: Averaging,
+    calibration: Calibration,
+    retries: u8,
+    config: Config,
+}
+
+impl<I2C: I2c> Sensor<I2C> {
+    /// Creates a new sensor instance.
+    pub fn new(i2c: I2C, address: AddressSelect) -> Self {
+        Self::with_address(i2c, address.into())
+    }
+
+    /// Creates a new sensor instance using a raw I²C address.
+    pub fn with_address(i2c: I2C, address: SevenBitAddress) -> Self {
+        Self {
+            i2c,
+            address,
+            averaging: Averaging::Max,
+            calibration: Calibration::IDENTITY,
+            retries: 0,
+            config: Config::default(),
+        }
+    }
+
+    /// Detects a sensor on either address.
+    ///
+    /// Both addresses are tried in turn, and the first one responding with the
+    /// expected device ID is used. Returns the bus if no sensor was detected.
+    pub async fn probe(i2c: I2C) -> Result<Self, I2C> {
+        let mut sensor = Self::new(i2c, AddressSelect::High);
+
+        for address in [AddressSelect::High, AddressSelect::Low] {
+            sensor.address = address.into();
+
+            if sensor.init().await.is_ok() {
+                return Ok(sensor);
+            }
+        }
+
+        Err(sensor.i2c)
+    }
+
+    /// I²C address of the sensor.
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    /// Destroys the sensor instance and returns the I²C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Number of times a failed transaction is retried.
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
+
+    /// Set the number of times a failed transaction is retried.
+    ///
+    /// See [`crate::Sensor::set_retries`].
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Creates a new sensor instance and checks that the device responds with
+    /// the expected device ID.
+    pub async fn new_checked(i2c: I2C, address: AddressSelect) -> Result<Self, Error<I2C::Error>> {
+        let mut sensor = Self::new(i2c, address);
+
+        sensor.init().await?;
+
+        Ok(sensor)
+    }
+
+    /// Initialise the sensor.
+    ///
+    /// See [`crate::Sensor::init`].
+    pub async fn init(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.check_device_id().await?;
+
+        self.modify_reg(with_interface_bits).await
+    }
+
+    /// Fails with [`Error::InvalidDeviceId`] unless the device ID register
+    /// reads 0xA0.
+    async fn check_device_id(&mut self) -> Result<(), Error<I2C::Error>> {
+        match self.read_device_id().await? {
+            DeviceId::EXPECTED => Ok(()),
+            id => Err(Error::InvalidDeviceId(id)),
+        }
+    }
+
+    /// Read device ID from the sensor.
+    ///
+    /// This is fixed number (0xA0).
+    pub async fn read_device_id(&mut self) -> Result<u8, Error<I2C::Error>> {
+        Ok(self.read_reg::<DeviceId>().await?.id())
+    }
+
+    /// Disable high temperature limit interrupt generation.
+    pub async fn disable_temperature_high_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(TempHighLimit::new(0)).await
+    }
+
+    /// Disable low temperature limit interrupt generation.
+    pub async fn disable_temperature_low_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(TempLowLimit::new(0)).await
+    }
+
+    /// Sets the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    pub async fn temperature_high_limit_centi(
+        &mut self,
+        centi: i32,
+    ) -> Result<(), Error<I2C::Error>> {
+        let value = centi_temperature_to_reg_value(centi).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempHighLimit::new(value)).await
+    }
+
+    /// Sets the temperature threshold high limit in degrees celcius.
+    #[cfg(feature = "float")]
+    pub async fn temperature_high_limit(&mut self, celcius: f32) -> Result<(), Error<I2C::Error>> {
+        let value = temperature_to_reg_value(celcius).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempHighLimit::new(value)).await
+    }
+
+    /// Reads the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub async fn read_temperature_high_limit_centi(
+        &mut self,
+    ) -> Result<Option<i32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempHighLimit>().await?.value();
+
+        Ok(reg_value_to_centi_temperature(value))
+    }
+
+    /// Reads the temperature threshold high limit in degrees celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    #[cfg(feature = "float")]
+    pub async fn read_temperature_high_limit(&mut self) -> Result<Option<f32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempHighLimit>().await?.value();
+
+        Ok(reg_value_to_temperature(value))
+    }
+
+    /// Sets the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    pub async fn temperature_low_limit_centi(
+        &mut self,
+        centi: i32,
+    ) -> Result<(), Error<I2C::Error>> {
+        let value = centi_temperature_to_reg_value(centi).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempLowLimit::new(value)).await
+    }
+
+    /// Sets the temperature threshold low limit in degrees celcius.
+    #[cfg(feature = "float")]
+    pub async fn temperature_low_limit(&mut self, celcius: f32) -> Result<(), Error<I2C::Error>> {
+        let value = temperature_to_reg_value(celcius).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempLowLimit::new(value)).await
+    }
+
+    /// Reads the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub async fn read_temperature_low_limit_centi(
+        &mut self,
+    ) -> Result<Option<i32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempLowLimit>().await?.value();
+
+        Ok(reg_value_to_centi_temperature(value))
+    }
+
+    /// Reads the temperature threshold low limit in degrees celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    #[cfg(feature = "float")]
+    pub async fn read_temperature_low_limit(&mut self) -> Result<Option<f32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempLowLimit>().await?.value();
+
+        Ok(reg_value_to_temperature(value))
+    }
+
+    /// Configure the operating mode of the sensor.
+    ///
+    /// See [`crate::Sensor::configure`].
+    pub async fn configure(&mut self, mode: Mode) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(mode.to_control_averaged(self.averaging))
+            .await
+    }
+
+    /// Averaging used for single conversions and the low output data rate.
+    pub fn averaging(&self) -> Averaging {
+        self.averaging
+    }
+
+    /// Set the averaging used for single conversions and the low output data
+    /// rate.
+    ///
+    /// See [`crate::Sensor::set_averaging`].
+    pub fn set_averaging(&mut self, averaging: Averaging) {
+        self.averaging = averaging;
+    }
+
+    /// Configure the sensor for a noise and power trade-off preset.
+    ///
+    /// See [`crate::Sensor::configure_profile`].
+    pub async fn configure_profile(&mut self, profile: Profile) -> Result<(), Error<I2C::Error>> {
+        self.set_averaging(profile.averaging());
+
+        self.configure(profile.mode()).await
+    }
+
+    /// Read the currently configured operating mode back from the sensor.
+    pub async fn read_configuration(&mut self) -> Result<Mode, Error<I2C::Error>> {
+        let control = self.read_reg::<Control>().await?;
+
+        Ok(Mode::from_control(control))
+    }
+
+    /// Calibration applied to temperature readings.
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    /// Set the calibration applied to temperature readings.
+    ///
+    /// See [`crate::Sensor::set_calibration`].
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    /// Read the temperature from the sensor in hundredths of degrees celcius.
+    ///
+    /// See [`crate::Sensor::read_temperature_centi`].
+    pub async fn read_temperature_centi(&mut self) -> Result<i16, Error<I2C::Error>> {
+        let mut buf: [u8; 2] = [0; 2];
+
+        self.read_registers(TempL::ADDRESS, &mut buf).await?;
+
+        Ok(self.calibration.apply(raw_to_temperature(buf)))
+    }
+
+    /// Read the temperature from the sensor in degrees celcius.
+    #[cfg(feature = "float")]
+    pub async fn read_temperature(&mut self) -> Result<f32, Error<I2C::Error>> {
+        Ok(centi_to_celcius(self.read_temperature_centi().await?))
+    }
+
+    /// Read the status register.
+    ///
+    /// The limit flags are cleared by the sensor when the register is read.
+    pub async fn read_status(&mut self) -> Result<Status, Error<I2C::Error>> {
+        let status = self.read_reg::<registers::Status>().await?;
+
+        Ok(Status::from(status))
+    }
+
+    /// Check whether a conversion is still in progress.
+    pub async fn is_busy(&mut self) -> Result<bool, Error<I2C::Error>> {
+        Ok(self.read_status().await?.busy)
+    }
+
+    /// Take a single temperature measurement.
+    ///
+    /// See [`crate::Sensor::measure_once_centi`].
+    pub async fn measure_once_centi<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<i16, Error<I2C::Error>> {
+        self.configure(Mode::SingleConversion).await?;
+
+        let mut poll = ConversionPoll::default();
+
+        loop {
+            delay.delay_us(poll.interval_us()).await;
+
+            if !self.is_busy().await? {
+                break;
+            }
+
+            if poll.timed_out() {
+                self.configure(Mode::PowerDown).await?;
+                return Err(Error::Timeout);
+            }
+        }
+
+        self.read_temperature_centi().await
+    }
+
+    /// Take a single temperature measurement in degrees celcius.
+    #[cfg(feature = "float")]
+    pub async fn measure_once<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<f32, Error<I2C::Error>> {
+        Ok(centi_to_celcius(self.measure_once_centi(delay).await?))
+    }
+
+    /// Wait for the sensor to signal a limit crossing.
+    ///
+    /// Awaits a falling edge on the (active low) interrupt pin and then reads
+    /// the status register to determine which limit was crossed.
+    pub async fn wait_for_alert<P: Wait>(
+        &mut self,
+        int: &mut P,
+    ) -> Result<Status, Error<I2C::Error>> {
+        int.wait_for_falling_edge()
+            .await
+            .map_err(|e| Error::Pin(e.kind()))?;
+
+        self.read_status().await
+    }
+
+    /// Read every register of the sensor.
+    ///
+    /// See [`crate::Sensor::diagnostics`].
+    pub async fn diagnostics(&mut self) -> Result<Diagnostics, Error<I2C::Error>> {
+        Ok(Diagnostics {
+            device_id: self.read_reg().await?,
+            high_limit: self.read_reg().await?,
+            low_limit: self.read_reg().await?,
+            control: self.read_reg().await?,
+            status: self.read_reg().await?,
+            temp_l: self.read_reg().await?,
+            temp_h: self.read_reg().await?,
+            soft_reset: self.read_reg().await?,
+        })
+    }
+
+    /// Perform a software reset of the sensor.
+    ///
+    /// Resets all digital blocks, waits for the sensor to come back and checks
+    /// the device ID. The operating mode and limits previously set through
+    /// this driver are restored afterwards.
+    pub async fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(SoftReset::default().with_sw_reset(true))
+            .await?;
+        self.write_reg(SoftReset::default()).await?;
+
+        delay.delay_us(RESET_TIME_US).await;
+
+        self.check_device_id().await?;
+
+        for (register, value) in self.config.restore() {
+            self.write_register(register, value).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Recover from a stuck bus and re-initialise the sensor.
+    ///
+    /// See [`crate::Sensor::recover`].
+    pub async fn recover<F: FnOnce(&mut I2C), D: DelayNs>(
+        &mut self,
+        bus_clear: F,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        bus_clear(&mut self.i2c);
+
+        self.reset(delay).await
+    }
+
+    /// Read a register.
+    pub async fn read_reg<R: Register>(&mut self) -> Result<R, Error<I2C::Error>> {
+        Ok(R::from_bits(self.read_register(R::ADDRESS).await?))
+    }
+
+    /// Write a register.
+    ///
+    /// Reserved bits are always written as zero. Block data update and
+    /// register address auto-increment are always set in the control
+    /// register, as burst reads rely on them.
+    pub async fn write_reg<R: Writable>(&mut self, register: R) -> Result<(), Error<I2C::Error>> {
+        self.write_register(R::ADDRESS, register.bits() & !R::RESERVED)
+            .await
+    }
+
+    /// Read a register, modify it and write it back.
+    pub async fn modify_reg<R: Writable, F: FnOnce(R) -> R>(
+        &mut self,
+        f: F,
+    ) -> Result<(), Error<I2C::Error>> {
+        let register = self.read_reg::<R>().await?;
+
+        self.write_reg(f(register)).await
+    }
+
+    /// Read a single register.
+    async fn read_register(&mut self, register: u8) -> Result<u8, Error<I2C::Error>> {
+        let mut buf: [u8; 1] = [0];
+
+        self.read_registers(register, &mut buf).await?;
+
+        Ok(buf[0])
+    }
+
+    /// Read consecutive registers in a single transfer.
+    async fn read_registers(
+        &mut self,
+        register: u8,
+        buf: &mut [u8],
+    ) -> Result<(), Error<I2C::Error>> {
+        let mut attempt = 0;
+
+        loop {
+            match self.i2c.write_read(self.address, &[register], buf).await {
+                Ok(()) => return Ok(()),
+                Err(e) if should_retry(&e, attempt, self.retries) => attempt += 1,
+                Err(e) => return Err(Error::I2c(e)),
+            }
+        }
+    }
+
+    /// Write a single register.
+    async fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<I2C::Error>> {
+        let value = register_value(register, value);
+
+        let mut attempt = 0;
+
+        loop {
+            match self.i2c.write(self.address, &[register, value]).await {
+                Ok(()) => break,
+                Err(e) if should_retry(&e, attempt, self.retries) => attempt += 1,
+                Err(e) => return Err(Error::I2c(e)),
+            }
+        }
+
+        self.config.record(register, value);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{Speed, CONVERSION_POLL_INTERVAL_US, CONVERSION_TIMEOUT_US};
+    use core::{
+        future::Future,
+        pin::pin,
+        task::{Context, Poll, Waker},
+    };
+    use embedded_hal::{digital, i2c::ErrorKind};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        digital::{Edge, Mock as PinMock, Transaction as PinTransaction},
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+        MockError,
+    };
+    use std::{io, vec};
+
+    /// Polls a future to completion. The mocks never return pending.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_configure() {
+        let modes = [
+            (Mode::PowerDown, 0b0100_1000),
+            (Mode::SingleConversion, 0b0100_1001),
+            (Mode::Continuous(Speed::Hz25), 0b0100_1100),
+            (Mode::Continuous(Speed::Hz200), 0b0111_1100),
+            (Mode::LowOdr, 0b1100_1000),
+        ];
+
+        for (mode, control) in modes {
+            let expectations = [
+                I2cTransaction::write(0x38, vec![Control::ADDRESS, control]),
+                I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![control]),
+            ];
+            let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+            block_on(sensor.configure(mode)).unwrap();
+            assert_eq!(block_on(sensor.read_configuration()).unwrap(), mode);
+
+            sensor.release().done();
+        }
+
+        // averaged single conversion
+        let expectations = [I2cTransaction::write(
+            0x38,
+            vec![Control::ADDRESS, 0b0111_1001],
+        )];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.set_averaging(Averaging::Min);
+        block_on(sensor.configure(Mode::SingleConversion)).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_read_temperature() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x5A, 0x0A]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(block_on(sensor.read_temperature_centi()).unwrap(), 2500);
+        assert_eq!(block_on(sensor.read_temperature_centi()).unwrap(), -1000);
+
+        sensor.set_calibration(Calibration::from_offset(2650, 2500));
+        assert_eq!(block_on(sensor.read_temperature_centi()).unwrap(), 2500);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_measure_once_timeout() {
+        let mut expectations = vec![I2cTransaction::write(
+            0x38,
+            vec![Control::ADDRESS, 0b0100_1001],
+        )];
+        for _ in 0..CONVERSION_TIMEOUT_US / CONVERSION_POLL_INTERVAL_US {
+            expectations.push(I2cTransaction::write_read(
+                0x38,
+                vec![registers::Status::ADDRESS],
+                vec![0b001],
+            ));
+        }
+        expectations.push(I2cTransaction::write(
+            0x38,
+            vec![Control::ADDRESS, 0b0100_1000],
+        ));
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(
+            block_on(sensor.measure_once_centi(&mut NoopDelay)),
+            Err(Error::Timeout)
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_reset_restores_configuration() {
+        let expectations = [
+            // configuration
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+            // reset
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        block_on(sensor.temperature_high_limit_centi(4000)).unwrap();
+        block_on(sensor.configure(Mode::Continuous(Speed::Hz100))).unwrap();
+        block_on(sensor.reset(&mut NoopDelay)).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_retries() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63]),
+            // not retried
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::Bus),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.set_retries(1);
+        assert_eq!(
+            block_on(sensor.read_device_id()).unwrap(),
+            DeviceId::EXPECTED
+        );
+        block_on(sensor.temperature_low_limit_centi(0)).unwrap();
+        assert_eq!(
+            block_on(sensor.read_temperature_centi()),
+            Err(Error::I2c(ErrorKind::Bus))
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_wait_for_alert() {
+        let expectations = [I2cTransaction::write_read(
+            0x38,
+            vec![registers::Status::ADDRESS],
+            vec![0b010],
+        )];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+        let mut int = PinMock::new(&[
+            PinTransaction::wait_for_edge(Edge::Falling),
+            PinTransaction::wait_for_edge(Edge::Falling)
+                .with_error(MockError::Io(io::ErrorKind::NotConnected)),
+        ]);
+
+        assert_eq!(
+            block_on(sensor.wait_for_alert(&mut int)).unwrap(),
+            Status {
+                busy: false,
+                over_high_limit: true,
+                under_low_limit: false,
+            }
+        );
+
+        // the status is not read on a pin error
+        assert_eq!(
+            block_on(sensor.wait_for_alert(&mut int)),
+            Err(Error::Pin(digital::ErrorKind::Other))
+        );
+
+        sensor.release().done();
+        int.done();
+    }
+}
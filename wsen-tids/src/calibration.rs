@@ -0,0 +1,214 @@
+//! User calibration of temperature readings.
+//!
+//! A [`Calibration`] corrects each reading with a linear gain and an offset,
+//! e.g. for a sensor mounted next to a heat source. It is applied by
+//! [`crate::Sensor::read_temperature_centi`] and everything built on top of
+//! it. The temperature limits are evaluated by the sensor itself and are not
+//! corrected.
+
+/// Unity gain in parts per million.
+pub const UNITY_GAIN_PPM: u32 = 1_000_000;
+
+/// Linear correction of temperature readings
+///
+/// The corrected temperature is `reading * gain_ppm / 1_000_000 + offset_centi`,
+/// rounded to the nearest hundredth of a degree.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Calibration {
+    /// Offset in hundredths of degrees celcius.
+    pub offset_centi: i32,
+    /// Gain in parts per million.
+    pub gain_ppm: u32,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Calibration {
+    /// Calibration leaving readings unchanged.
+    pub const IDENTITY: Self = Self {
+        offset_centi: 0,
+        gain_ppm: UNITY_GAIN_PPM,
+    };
+
+    /// Length of the serialized calibration record.
+    pub const RECORD_LEN: usize = 9;
+
+    /// Creates a calibration that only corrects an offset.
+    ///
+    /// Both temperatures are in hundredths of degrees celcius, `reading` as
+    /// read from the uncalibrated sensor.
+    pub fn from_offset(reading: i16, reference: i16) -> Self {
+        Self {
+            offset_centi: reference as i32 - reading as i32,
+            gain_ppm: UNITY_GAIN_PPM,
+        }
+    }
+
+    /// Creates a calibration from two readings of the uncalibrated sensor and
+    /// the matching reference temperatures.
+    ///
+    /// All temperatures are in hundredths of degrees celcius. Returns `None` if
+    /// the readings are equal or the resulting gain is not positive.
+    pub fn two_point(
+        (reading_a, reference_a): (i16, i16),
+        (reading_b, reference_b): (i16, i16),
+    ) -> Option<Self> {
+        let readings = reading_b as i64 - reading_a as i64;
+        let references = reference_b as i64 - reference_a as i64;
+
+        if readings == 0 {
+            return None;
+        }
+
+        let gain_ppm = div_round(references * UNITY_GAIN_PPM as i64, readings);
+        let gain_ppm = u32::try_from(gain_ppm).ok().filter(|&gain| gain > 0)?;
+
+        let offset_centi = reference_a as i64
+            - div_round(reading_a as i64 * gain_ppm as i64, UNITY_GAIN_PPM as i64);
+
+        Some(Self {
+            offset_centi: i32::try_from(offset_centi).ok()?,
+            gain_ppm,
+        })
+    }
+
+    /// Corrects a reading in hundredths of degrees celcius.
+    ///
+    /// The result saturates at the limits of `i16`.
+    pub fn apply(&self, centi: i16) -> i16 {
+        let scaled = div_round(centi as i64 * self.gain_ppm as i64, UNITY_GAIN_PPM as i64);
+        let corrected = scaled + self.offset_centi as i64;
+
+        corrected.clamp(i16::MIN as i64, i16::MAX as i64) as i16
+    }
+
+    /// Serializes the calibration for storage.
+    ///
+    /// The record holds the offset and the gain in little endian, followed by
+    /// a CRC-8 check byte.
+    pub fn to_bytes(&self) -> [u8; Self::RECORD_LEN] {
+        let mut record = [0; Self::RECORD_LEN];
+
+        record[0..4].copy_from_slice(&self.offset_centi.to_le_bytes());
+        record[4..8].copy_from_slice(&self.gain_ppm.to_le_bytes());
+        record[8] = crc8(&record[0..8]);
+
+        record
+    }
+
+    /// Deserializes a calibration record.
+    ///
+    /// Returns `None` if the check byte does not match, e.g. for erased or
+    /// corrupted storage, or the gain is zero.
+    pub fn from_bytes(record: &[u8; Self::RECORD_LEN]) -> Option<Self> {
+        if crc8(&record[0..8]) != record[8] {
+            return None;
+        }
+
+        let mut offset = [0; 4];
+        let mut gain = [0; 4];
+        offset.copy_from_slice(&record[0..4]);
+        gain.copy_from_slice(&record[4..8]);
+
+        let calibration = Self {
+            offset_centi: i32::from_le_bytes(offset),
+            gain_ppm: u32::from_le_bytes(gain),
+        };
+
+        (calibration.gain_ppm > 0).then_some(calibration)
+    }
+}
+
+/// Divides, rounding half away from zero.
+fn div_round(numerator: i64, denominator: i64) -> i64 {
+    let half = denominator.abs() / 2;
+
+    if (numerator < 0) == (denominator < 0) {
+        (numerator.abs() + half) / denominator.abs()
+    } else {
+        -((numerator.abs() + half) / denominator.abs())
+    }
+}
+
+/// CRC-8 with polynomial 0x07 and initial value 0xFF.
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0xFF;
+
+    for &byte in data {
+        crc ^= byte;
+
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Calibration::IDENTITY.apply(2500), 2500);
+        assert_eq!(Calibration::IDENTITY.apply(-1000), -1000);
+
+        let offset = Calibration::from_offset(2650, 2500);
+        assert_eq!(offset.offset_centi, -150);
+        assert_eq!(offset.apply(2650), 2500);
+
+        let calibration = Calibration {
+            offset_centi: 100,
+            gain_ppm: 1_010_000,
+        };
+        assert_eq!(calibration.apply(2000), 2120);
+        assert_eq!(calibration.apply(-1000), -910);
+
+        let calibration = Calibration {
+            offset_centi: 1000,
+            gain_ppm: 2 * UNITY_GAIN_PPM,
+        };
+        assert_eq!(calibration.apply(i16::MAX), i16::MAX);
+        assert_eq!(calibration.apply(i16::MIN), i16::MIN);
+    }
+
+    #[test]
+    fn test_two_point() {
+        // reads 1 °C high at 0 °C and 3 °C high at 100 °C
+        let calibration = Calibration::two_point((100, 0), (10300, 10000)).unwrap();
+        assert_eq!(calibration.gain_ppm, 980_392);
+        assert_eq!(calibration.apply(100), 0);
+        assert_eq!(calibration.apply(10300), 10000);
+        assert_eq!(calibration.apply(5200), 5000);
+
+        assert_eq!(Calibration::two_point((100, 0), (100, 1000)), None);
+        assert_eq!(Calibration::two_point((0, 1000), (1000, 0)), None);
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let calibration = Calibration {
+            offset_centi: -150,
+            gain_ppm: 980_392,
+        };
+        let record = calibration.to_bytes();
+        assert_eq!(Calibration::from_bytes(&record), Some(calibration));
+
+        let mut corrupted = record;
+        corrupted[2] ^= 0x01;
+        assert_eq!(Calibration::from_bytes(&corrupted), None);
+
+        // erased flash
+        assert_eq!(Calibration::from_bytes(&[0xFF; 9]), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! Register snapshot for diagnosing misbehaving sensors.
+
+use crate::{
+    raw_to_temperature, reg_value_to_centi_temperature,
+    registers::{
+        Control, DeviceId, Register, SoftReset, Status, TempH, TempHighLimit, TempL, TempLowLimit,
+    },
+};
+
+/// Contents of every register in the map
+///
+/// Returned by [`crate::Sensor::diagnostics`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Diagnostics {
+    pub device_id: DeviceId,
+    pub high_limit: TempHighLimit,
+    pub low_limit: TempLowLimit,
+    pub control: Control,
+    pub status: Status,
+    pub temp_l: TempL,
+    pub temp_h: TempH,
+    pub soft_reset: SoftReset,
+}
+
+/// Inconsistencies found in a [`Diagnostics`] snapshot
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Issues {
+    /// The device ID register does not read 0xA0.
+    pub invalid_device_id: bool,
+    /// The busy flag is set although no conversion is configured.
+    pub stuck_busy: bool,
+    /// Reserved bits are set in any register.
+    pub reserved_bits: bool,
+    /// Both limits are enabled, but the high limit is not above the low limit.
+    pub inverted_limits: bool,
+    /// The software reset bit has not been cleared.
+    pub reset_pending: bool,
+}
+
+impl Issues {
+    /// Whether any issue was found.
+    pub fn any(&self) -> bool {
+        self.invalid_device_id
+            || self.stuck_busy
+            || self.reserved_bits
+            || self.inverted_limits
+            || self.reset_pending
+    }
+}
+
+impl Diagnostics {
+    /// Temperature held by the data registers in hundredths of degrees
+    /// celcius, without calibration.
+    pub fn temperature_centi(&self) -> i16 {
+        raw_to_temperature([self.temp_l.value(), self.temp_h.value()])
+    }
+
+    /// Checks the snapshot for inconsistencies.
+    pub fn issues(&self) -> Issues {
+        let control = self.control;
+        let converting = control.one_shot() || control.freerun() || control.low_odr_start();
+
+        let reserved = self.device_id.reserved_bits()
+            | self.high_limit.reserved_bits()
+            | self.low_limit.reserved_bits()
+            | self.control.reserved_bits()
+            | self.status.reserved_bits()
+            | self.temp_l.reserved_bits()
+            | self.temp_h.reserved_bits()
+            | self.soft_reset.reserved_bits();
+
+        let high_limit = reg_value_to_centi_temperature(self.high_limit.value());
+        let low_limit = reg_value_to_centi_temperature(self.low_limit.value());
+
+        Issues {
+            invalid_device_id: self.device_id.id() != DeviceId::EXPECTED,
+            stuck_busy: self.status.busy() && !converting,
+            reserved_bits: reserved != 0,
+            inverted_limits: matches!((high_limit, low_limit), (Some(high), Some(low)) if high <= low),
+            reset_pending: self.soft_reset.sw_reset(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> Diagnostics {
+        Diagnostics {
+            device_id: DeviceId::from_bits(DeviceId::EXPECTED),
+            high_limit: TempHighLimit::new(125),
+            low_limit: TempLowLimit::new(63),
+            control: Control::from_bits(0b0100_1100),
+            status: Status::from_bits(0b001),
+            temp_l: TempL::from_bits(0xC4),
+            temp_h: TempH::from_bits(0x09),
+            soft_reset: SoftReset::default(),
+        }
+    }
+
+    #[test]
+    fn test_issues() {
+        let diagnostics = healthy();
+        assert_eq!(diagnostics.temperature_centi(), 2500);
+        assert!(!diagnostics.issues().any());
+
+        let diagnostics = Diagnostics {
+            device_id: DeviceId::from_bits(0xFF),
+            control: Control::from_bits(0b0100_1000),
+            ..healthy()
+        };
+        let issues = diagnostics.issues();
+        assert!(issues.invalid_device_id);
+        assert!(issues.stuck_busy);
+        assert!(!issues.reserved_bits);
+
+        let diagnostics = Diagnostics {
+            status: Status::from_bits(0b1000_0000),
+            soft_reset: SoftReset::from_bits(0b0000_0011),
+            ..healthy()
+        };
+        let issues = diagnostics.issues();
+        assert!(issues.reserved_bits);
+        assert!(issues.reset_pending);
+
+        let diagnostics = Diagnostics {
+            low_limit: TempLowLimit::new(125),
+            ..healthy()
+        };
+        assert_eq!(
+            diagnostics.issues(),
+            Issues {
+                inverted_limits: true,
+                ..Issues::default()
+            }
+        );
+
+        // a disabled limit is never inverted
+        let diagnostics = Diagnostics {
+            high_limit: TempHighLimit::new(0),
+            ..healthy()
+        };
+        assert!(!diagnostics.issues().inverted_limits);
+    }
+}
@@ -1,31 +1,77 @@
 #![no_std]
 
-use embedded_hal::i2c::{I2c, SevenBitAddress};
+pub mod alarm;
+pub mod array;
+#[cfg(any(test, feature = "async"))]
+pub mod asynch;
+pub mod calibration;
+pub mod diagnostics;
+pub mod registers;
+#[cfg(any(test, feature = "sim"))]
+pub mod sim;
+pub mod stream;
+pub mod typestate;
+#[cfg(feature = "uom")]
+pub mod units;
+
+use calibration::Calibration;
+use diagnostics::Diagnostics;
+use embedded_hal::{
+    delay::DelayNs,
+    digital,
+    i2c::{self, I2c, SevenBitAddress},
+};
+use registers::{
+    Control, DeviceId, Register, SoftReset, TempHighLimit, TempL, TempLowLimit, Writable,
+};
 
 /// I²C device address selection
-#[derive(Copy, Clone)]
+///
+/// Selected by the level of the SAO pin. Note that, as listed in the user
+/// manual, tying SAO high selects the lower of the two addresses.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AddressSelect {
+    /// SAO connected to the supply voltage.
     High = 0b0111000,
+    /// SAO connected to ground.
     Low = 0b0111111,
 }
 
-impl Into<SevenBitAddress> for AddressSelect {
-    fn into(self) -> SevenBitAddress {
-        self as u8
+impl From<AddressSelect> for SevenBitAddress {
+    fn from(value: AddressSelect) -> Self {
+        value as u8
+    }
+}
+
+impl TryFrom<SevenBitAddress> for AddressSelect {
+    type Error = SevenBitAddress;
+
+    fn try_from(value: SevenBitAddress) -> Result<Self, Self::Error> {
+        match value {
+            0b0111000 => Ok(AddressSelect::High),
+            0b0111111 => Ok(AddressSelect::Low),
+            _ => Err(value),
+        }
     }
 }
 
-// register offsets
-const REG_DEVICE_ID: u8 = 0x01;
-const REG_TEMP_HIGH_LIMIT: u8 = 0x02;
-const REG_TEMP_LOW_LIMIT: u8 = 0x03;
-const REG_CONTROL: u8 = 0x04;
-const REG_STATUS: u8 = 0x05;
-const REG_DATA_TEMP_L: u8 = 0x06;
-const REG_DATA_TEMP_H: u8 = 0x07;
-const REG_SOFT_RESET: u8 = 0x0C;
+/// Lowest temperature limit in hundredths of degrees celcius.
+pub const TEMPERATURE_LIMIT_MIN_CENTI: i32 = -3968;
+
+/// Highest temperature limit in hundredths of degrees celcius.
+pub const TEMPERATURE_LIMIT_MAX_CENTI: i32 = 12288;
+
+// time for the sensor to come back after a soft reset
+const RESET_TIME_US: u32 = 1_000;
+
+// single conversion timing
+const CONVERSION_POLL_INTERVAL_US: u32 = 1_000;
+const CONVERSION_TIMEOUT_US: u32 = 100_000;
 
 /// Continuous conversion speed
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Speed {
     Hz25 = 0b00,
     Hz50 = 0b01,
@@ -33,127 +79,1674 @@ pub enum Speed {
     Hz200 = 0b11,
 }
 
+impl Speed {
+    /// Decodes the two `FREQ` bits of the control register.
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Speed::Hz25,
+            0b01 => Speed::Hz50,
+            0b10 => Speed::Hz100,
+            _ => Speed::Hz200,
+        }
+    }
+
+    /// Conversion period in microseconds.
+    pub fn period_us(self) -> u32 {
+        match self {
+            Speed::Hz25 => 40_000,
+            Speed::Hz50 => 20_000,
+            Speed::Hz100 => 10_000,
+            Speed::Hz200 => 5_000,
+        }
+    }
+}
+
+/// Internal averaging
+///
+/// Selected by the `AVG` bits of the control register, which set the
+/// [`Speed`] in continuous mode. For single conversions and the low output
+/// data rate the driver writes the setting chosen with
+/// [`Sensor::set_averaging`] to the same bits. Each variant is named after the
+/// continuous speed sharing its bit pattern.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Averaging {
+    /// Averaging of continuous conversion at 25 Hz.
+    Max = 0b00,
+    /// Averaging of continuous conversion at 50 Hz.
+    High = 0b01,
+    /// Averaging of continuous conversion at 100 Hz.
+    Low = 0b10,
+    /// Averaging of continuous conversion at 200 Hz.
+    Min = 0b11,
+}
+
+impl Averaging {
+    /// Decodes the two `AVG` bits of the control register.
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Averaging::Max,
+            0b01 => Averaging::High,
+            0b10 => Averaging::Low,
+            _ => Averaging::Min,
+        }
+    }
+
+    /// Conversion period of the continuous [`Speed`] sharing the setting, in
+    /// microseconds.
+    ///
+    /// This is an upper bound for the conversion time, not the conversion
+    /// time itself.
+    pub fn max_conversion_time_us(self) -> u32 {
+        Speed::from_bits(self as u8).period_us()
+    }
+}
+
+impl From<Speed> for Averaging {
+    fn from(speed: Speed) -> Self {
+        Self::from_bits(speed as u8)
+    }
+}
+
 /// Sensor operating mode
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Mode {
     PowerDown,
+    /// Trigger a single conversion, after which the sensor returns to power
+    /// down.
     SingleConversion,
     Continuous(Speed),
+    /// Continuous conversion at the 1 Hz low output data rate.
+    LowOdr,
+}
+
+impl Mode {
+    /// Encodes the mode into the control register.
+    fn to_control(self) -> Control {
+        match self {
+            Mode::PowerDown => Control::default(),
+            Mode::SingleConversion => Control::default().with_one_shot(true),
+            Mode::Continuous(speed) => Control::default().with_freerun(true).with_freq(speed),
+            Mode::LowOdr => Control::default().with_low_odr_start(true),
+        }
+    }
+
+    /// Encodes the mode into the control register, using the averaging for
+    /// the modes without a continuous speed.
+    fn to_control_averaged(self, averaging: Averaging) -> Control {
+        match self {
+            Mode::SingleConversion | Mode::LowOdr => self.to_control().with_avg(averaging),
+            Mode::PowerDown | Mode::Continuous(_) => self.to_control(),
+        }
+    }
+
+    /// Decodes the mode from the control register.
+    fn from_control(control: Control) -> Self {
+        if control.freerun() {
+            Mode::Continuous(control.freq())
+        } else if control.low_odr_start() {
+            Mode::LowOdr
+        } else if control.one_shot() {
+            Mode::SingleConversion
+        } else {
+            Mode::PowerDown
+        }
+    }
+}
+
+/// Noise and power trade-off presets
+///
+/// Each profile selects an operating mode together with the averaging, and
+/// can be applied with [`Sensor::configure_profile`]. The averaging is also
+/// used by later single conversions.
+///
+/// | Profile               | Mode                        | Averaging           | Supply current (typ.) |
+/// |-----------------------|-----------------------------|---------------------|-----------------------|
+/// | [`Profile::LowPower`] | [`Mode::LowOdr`], 1 Hz      | [`Averaging::Min`]  | 1.75 µA               |
+/// | [`Profile::Balanced`] | [`Mode::Continuous`], 50 Hz | [`Averaging::High`] | see user manual       |
+/// | [`Profile::LowNoise`] | [`Mode::Continuous`], 25 Hz | [`Averaging::Max`]  | see user manual       |
+///
+/// The low power profile converts once per second and is powered down in
+/// between. The continuous profiles convert all the time, refer to the
+/// electrical characteristics in the user manual for their current draw.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Profile {
+    /// Least averaging at the lowest output data rate.
+    LowPower,
+    /// Moderate averaging and output data rate.
+    Balanced,
+    /// Longest averaging.
+    LowNoise,
+}
+
+impl Profile {
+    /// Operating mode of the profile.
+    pub fn mode(self) -> Mode {
+        match self {
+            Profile::LowPower => Mode::LowOdr,
+            Profile::Balanced => Mode::Continuous(Speed::Hz50),
+            Profile::LowNoise => Mode::Continuous(Speed::Hz25),
+        }
+    }
+
+    /// Averaging of the profile.
+    pub fn averaging(self) -> Averaging {
+        match self {
+            Profile::LowPower => Averaging::Min,
+            Profile::Balanced => Averaging::High,
+            Profile::LowNoise => Averaging::Max,
+        }
+    }
+}
+
+/// Driver error
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// I²C bus error.
+    I2c(E),
+    /// Interrupt pin error.
+    Pin(digital::ErrorKind),
+    /// The device ID register did not contain the expected value.
+    InvalidDeviceId(u8),
+    /// A conversion did not complete in time.
+    Timeout,
+    /// The temperature limit cannot be represented by the sensor.
+    LimitOutOfRange,
+}
+
+/// Class of an I²C bus error
+///
+/// Returned by [`Error::bus_fault`] to decide how to handle a failed
+/// transaction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BusFault {
+    /// The sensor did not acknowledge its address or data. It may be absent
+    /// or not yet ready, e.g. during a reset.
+    NoAcknowledge,
+    /// Another controller won arbitration, the transaction can be retried.
+    ArbitrationLoss,
+    /// Misplaced start or stop condition, e.g. after a glitch on the bus. If
+    /// it persists, the bus may be held low and needs [`Sensor::recover`].
+    Bus,
+    /// Any other error of the I²C peripheral.
+    Other,
 }
 
+impl BusFault {
+    /// Whether a failed transaction may succeed when repeated.
+    ///
+    /// This is the case for a missing acknowledge and lost arbitration, and
+    /// only these are retried by the driver, see [`Sensor::set_retries`].
+    pub fn is_transient(self) -> bool {
+        matches!(self, BusFault::NoAcknowledge | BusFault::ArbitrationLoss)
+    }
+}
+
+impl From<i2c::ErrorKind> for BusFault {
+    fn from(kind: i2c::ErrorKind) -> Self {
+        match kind {
+            i2c::ErrorKind::NoAcknowledge(_) => BusFault::NoAcknowledge,
+            i2c::ErrorKind::ArbitrationLoss => BusFault::ArbitrationLoss,
+            i2c::ErrorKind::Bus => BusFault::Bus,
+            _ => BusFault::Other,
+        }
+    }
+}
+
+impl<E: i2c::Error> Error<E> {
+    /// Classifies an I²C bus error.
+    ///
+    /// Returns `None` for errors not caused by the bus.
+    pub fn bus_fault(&self) -> Option<BusFault> {
+        match self {
+            Error::I2c(e) => Some(BusFault::from(e.kind())),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a failed transaction is repeated after `attempt` retries.
+pub(crate) fn should_retry<E: i2c::Error>(error: &E, attempt: u8, retries: u8) -> bool {
+    attempt < retries && BusFault::from(error.kind()).is_transient()
+}
+
+/// Sensor status flags
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status {
+    /// A conversion is in progress.
+    pub busy: bool,
+    /// The temperature exceeded the high limit.
+    pub over_high_limit: bool,
+    /// The temperature dropped below the low limit.
+    pub under_low_limit: bool,
+}
+
+impl From<registers::Status> for Status {
+    fn from(value: registers::Status) -> Self {
+        Self {
+            busy: value.busy(),
+            over_high_limit: value.over_thl(),
+            under_low_limit: value.under_tll(),
+        }
+    }
+}
+
+impl From<u8> for Status {
+    fn from(value: u8) -> Self {
+        Self::from(registers::Status::from_bits(value))
+    }
+}
+
+/// WSEN-TIDS temperature sensor
+///
+/// The sensor takes ownership of the I²C bus, which can be returned with
+/// [`Sensor::release`]. To share a bus with other sensors or drivers, pass each
+/// of them a bus device from `embedded-hal-bus`, such as `RefCellDevice` (all
+/// users in one execution context) or `CriticalSectionDevice` (users in
+/// different interrupt priorities). Up to two sensors can share a bus, one at
+/// each [`AddressSelect`] address.
+#[derive(Debug)]
 pub struct Sensor<I2C> {
     i2c: I2C,
-    address: u8,
+    address: SevenBitAddress,
+    averaging: Averaging,
+    calibration: Calibration,
+    retries: u8,
+    config: Config,
 }
 
 impl<I2C: I2c> Sensor<I2C> {
     /// Creates a new sensor instance.
     pub fn new(i2c: I2C, address: AddressSelect) -> Self {
+        Self::with_address(i2c, address.into())
+    }
+
+    /// Creates a new sensor instance using a raw I²C address.
+    pub fn with_address(i2c: I2C, address: SevenBitAddress) -> Self {
         Self {
             i2c,
-            address: address.into(),
+            address,
+            averaging: Averaging::Max,
+            calibration: Calibration::IDENTITY,
+            retries: 0,
+            config: Config::default(),
         }
     }
 
-    /// Read device ID from the sensor.
+    /// Detects a sensor on either address.
     ///
-    /// This is fixed number (0xA0).
-    pub fn read_device_id(&mut self) -> Result<u8, I2C::Error> {
-        let mut buf: [u8; 1] = [0];
+    /// Both addresses are tried in turn, and the first one responding with the
+    /// expected device ID is used. Returns the bus if no sensor was detected.
+    pub fn probe(i2c: I2C) -> Result<Self, I2C> {
+        let mut sensor = Self::new(i2c, AddressSelect::High);
+
+        for address in [AddressSelect::High, AddressSelect::Low] {
+            sensor.address = address.into();
+
+            if sensor.init().is_ok() {
+                return Ok(sensor);
+            }
+        }
+
+        Err(sensor.i2c)
+    }
+
+    /// I²C address of the sensor.
+    pub fn address(&self) -> SevenBitAddress {
+        self.address
+    }
+
+    /// Destroys the sensor instance and returns the I²C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Number of times a failed transaction is retried.
+    pub fn retries(&self) -> u8 {
+        self.retries
+    }
 
-        match self.i2c.read(self.address + REG_DEVICE_ID, &mut buf) {
-            Ok(_) => Ok(buf[0]),
-            Err(e) => Err(e),
+    /// Set the number of times a failed transaction is retried before the
+    /// error is returned.
+    ///
+    /// Defaults to 0. Only transient faults are retried, see
+    /// [`BusFault::is_transient`]. Any other fault is returned straight away,
+    /// as retries do not help with a bus held low, see [`Sensor::recover`].
+    pub fn set_retries(&mut self, retries: u8) {
+        self.retries = retries;
+    }
+
+    /// Creates a new sensor instance and checks that the device responds with
+    /// the expected device ID.
+    pub fn new_checked(i2c: I2C, address: AddressSelect) -> Result<Self, Error<I2C::Error>> {
+        let mut sensor = Self::new(i2c, address);
+
+        sensor.init()?;
+
+        Ok(sensor)
+    }
+
+    /// Initialise the sensor.
+    ///
+    /// Fails with [`Error::InvalidDeviceId`] unless the device ID register
+    /// reads 0xA0. Enables block data update and register address
+    /// auto-increment, keeping the operating mode.
+    pub fn init(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.check_device_id()?;
+
+        self.modify_reg(with_interface_bits)
+    }
+
+    /// Fails with [`Error::InvalidDeviceId`] unless the device ID register
+    /// reads 0xA0.
+    fn check_device_id(&mut self) -> Result<(), Error<I2C::Error>> {
+        match self.read_device_id()? {
+            DeviceId::EXPECTED => Ok(()),
+            id => Err(Error::InvalidDeviceId(id)),
         }
     }
 
+    /// Read device ID from the sensor.
+    ///
+    /// This is fixed number (0xA0).
+    pub fn read_device_id(&mut self) -> Result<u8, Error<I2C::Error>> {
+        Ok(self.read_reg::<DeviceId>()?.id())
+    }
+
     /// Disable high temperature limit interrupt generation.
-    pub fn disable_temperature_high_limit(&mut self) -> Result<(), I2C::Error> {
-        self.i2c.write(self.address + REG_TEMP_HIGH_LIMIT, &[0])
+    pub fn disable_temperature_high_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(TempHighLimit::new(0))
     }
 
     /// Disable low temperature limit interrupt generation.
-    pub fn disable_temperature_low_limit(&mut self) -> Result<(), I2C::Error> {
-        self.i2c.write(self.address + REG_TEMP_LOW_LIMIT, &[0])
+    pub fn disable_temperature_low_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(TempLowLimit::new(0))
     }
 
-    /// Sets the temperature threshold high limit in degrees celcius.
-    pub fn temperature_high_limit(&mut self, celcius: f32) -> Result<(), I2C::Error> {
-        let value = temperature_to_reg_value(celcius);
+    /// Sets the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Fails with [`Error::LimitOutOfRange`] unless the limit is within
+    /// [`TEMPERATURE_LIMIT_MIN_CENTI`] and [`TEMPERATURE_LIMIT_MAX_CENTI`].
+    pub fn temperature_high_limit_centi(&mut self, centi: i32) -> Result<(), Error<I2C::Error>> {
+        let value = centi_temperature_to_reg_value(centi).ok_or(Error::LimitOutOfRange)?;
 
-        self.i2c.write(self.address + REG_TEMP_HIGH_LIMIT, &[value])
+        self.write_reg(TempHighLimit::new(value))
     }
 
     /// Sets the temperature threshold high limit in degrees celcius.
-    pub fn temperature_low_limit(&mut self, celcius: f32) -> Result<(), I2C::Error> {
-        let value = temperature_to_reg_value(celcius);
+    ///
+    /// See [`Sensor::temperature_high_limit_centi`].
+    #[cfg(feature = "float")]
+    pub fn temperature_high_limit(&mut self, celcius: f32) -> Result<(), Error<I2C::Error>> {
+        let value = temperature_to_reg_value(celcius).ok_or(Error::LimitOutOfRange)?;
 
-        self.i2c.write(self.address + REG_TEMP_LOW_LIMIT, &[value])
+        self.write_reg(TempHighLimit::new(value))
     }
 
-    pub fn configure(&mut self, mode: Mode) -> Result<(), I2C::Error> {
-        todo!("Implement register configuration");
-        let value = match mode {
-            Mode::PowerDown => 0,
-            Mode::SingleConversion => 0,
-            Mode::Continuous(_) => 0,
-        };
+    /// Reads the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub fn read_temperature_high_limit_centi(&mut self) -> Result<Option<i32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempHighLimit>()?.value();
 
-        self.i2c.write(self.address + REG_CONTROL, &[value])
+        Ok(reg_value_to_centi_temperature(value))
     }
 
-    /// Read the temperature from the sensor.
-    pub fn read_temperature(&mut self) -> Result<f32, I2C::Error> {
-        let mut buf: [u8; 1] = [0];
+    /// Reads the temperature threshold high limit in degrees celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    #[cfg(feature = "float")]
+    pub fn read_temperature_high_limit(&mut self) -> Result<Option<f32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempHighLimit>()?.value();
 
-        let low: u16 = match self.i2c.read(self.address + REG_DATA_TEMP_L, &mut buf) {
-            Ok(_) => buf[0] as u16,
-            Err(e) => return Err(e),
-        };
+        Ok(reg_value_to_temperature(value))
+    }
 
-        let mut buf: [u8; 1] = [0];
+    /// Sets the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Fails with [`Error::LimitOutOfRange`] unless the limit is within
+    /// [`TEMPERATURE_LIMIT_MIN_CENTI`] and [`TEMPERATURE_LIMIT_MAX_CENTI`].
+    pub fn temperature_low_limit_centi(&mut self, centi: i32) -> Result<(), Error<I2C::Error>> {
+        let value = centi_temperature_to_reg_value(centi).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempLowLimit::new(value))
+    }
+
+    /// Sets the temperature threshold low limit in degrees celcius.
+    ///
+    /// See [`Sensor::temperature_low_limit_centi`].
+    #[cfg(feature = "float")]
+    pub fn temperature_low_limit(&mut self, celcius: f32) -> Result<(), Error<I2C::Error>> {
+        let value = temperature_to_reg_value(celcius).ok_or(Error::LimitOutOfRange)?;
+
+        self.write_reg(TempLowLimit::new(value))
+    }
+
+    /// Reads the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub fn read_temperature_low_limit_centi(&mut self) -> Result<Option<i32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempLowLimit>()?.value();
+
+        Ok(reg_value_to_centi_temperature(value))
+    }
+
+    /// Reads the temperature threshold low limit in degrees celcius.
+    ///
+    /// Returns `None` if the limit is disabled.
+    #[cfg(feature = "float")]
+    pub fn read_temperature_low_limit(&mut self) -> Result<Option<f32>, Error<I2C::Error>> {
+        let value = self.read_reg::<TempLowLimit>()?.value();
+
+        Ok(reg_value_to_temperature(value))
+    }
+
+    /// Configure the operating mode of the sensor.
+    ///
+    /// Configuring [`Mode::SingleConversion`] starts a conversion straight
+    /// away. Block data update and register address auto-increment are always
+    /// enabled.
+    pub fn configure(&mut self, mode: Mode) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(mode.to_control_averaged(self.averaging))
+    }
+
+    /// Averaging used for single conversions and the low output data rate.
+    pub fn averaging(&self) -> Averaging {
+        self.averaging
+    }
+
+    /// Set the averaging used for single conversions and the low output data
+    /// rate.
+    ///
+    /// Takes effect the next time such a mode is configured. In continuous
+    /// mode the averaging is given by the [`Speed`].
+    pub fn set_averaging(&mut self, averaging: Averaging) {
+        self.averaging = averaging;
+    }
+
+    /// Configure the sensor for a noise and power trade-off preset.
+    ///
+    /// Sets the averaging of the profile and configures its mode.
+    pub fn configure_profile(&mut self, profile: Profile) -> Result<(), Error<I2C::Error>> {
+        self.set_averaging(profile.averaging());
+
+        self.configure(profile.mode())
+    }
+
+    /// Read the currently configured operating mode back from the sensor.
+    ///
+    /// While a single conversion is in progress this returns
+    /// [`Mode::SingleConversion`], afterwards [`Mode::PowerDown`].
+    pub fn read_configuration(&mut self) -> Result<Mode, Error<I2C::Error>> {
+        let control = self.read_reg::<Control>()?;
+
+        Ok(Mode::from_control(control))
+    }
+
+    /// Calibration applied to temperature readings.
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    /// Set the calibration applied to temperature readings.
+    ///
+    /// Use [`Calibration::IDENTITY`] while taking the readings to calibrate
+    /// from.
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    /// Read the temperature from the sensor in hundredths of degrees celcius.
+    ///
+    /// Both data registers are read in a single transfer, which relies on the
+    /// register address auto-increment enabled by [`Sensor::configure`]. The
+    /// [`Calibration`] is applied to the result.
+    pub fn read_temperature_centi(&mut self) -> Result<i16, Error<I2C::Error>> {
+        let mut buf: [u8; 2] = [0; 2];
+
+        self.read_registers(TempL::ADDRESS, &mut buf)?;
+
+        Ok(self.calibration.apply(raw_to_temperature(buf)))
+    }
+
+    /// Read the temperature from the sensor in degrees celcius.
+    #[cfg(feature = "float")]
+    pub fn read_temperature(&mut self) -> Result<f32, Error<I2C::Error>> {
+        Ok(centi_to_celcius(self.read_temperature_centi()?))
+    }
+
+    /// Read the status register.
+    ///
+    /// The limit flags are cleared by the sensor when the register is read.
+    pub fn read_status(&mut self) -> Result<Status, Error<I2C::Error>> {
+        let status = self.read_reg::<registers::Status>()?;
+
+        Ok(Status::from(status))
+    }
+
+    /// Check whether a conversion is still in progress.
+    pub fn is_busy(&mut self) -> Result<bool, Error<I2C::Error>> {
+        Ok(self.read_status()?.busy)
+    }
+
+    /// Take a single temperature measurement.
+    ///
+    /// Triggers a single conversion and polls the busy flag until it
+    /// completes. The sensor is left powered down afterwards.
+    ///
+    /// The result is in hundredths of degrees celcius. Returns
+    /// [`Error::Timeout`] if the conversion did not complete within 100 ms.
+    pub fn measure_once_centi<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<i16, Error<I2C::Error>> {
+        self.configure(Mode::SingleConversion)?;
+
+        let mut poll = ConversionPoll::default();
 
-        let high: u16 = match self.i2c.read(self.address + REG_DATA_TEMP_H, &mut buf) {
-            Ok(_) => buf[0] as u16,
-            Err(e) => return Err(e),
-        };
+        loop {
+            delay.delay_us(poll.interval_us());
 
-        let composite: f32 = (high << 8 | low) as f32;
+            if !self.is_busy()? {
+                break;
+            }
+
+            if poll.timed_out() {
+                self.configure(Mode::PowerDown)?;
+                return Err(Error::Timeout);
+            }
+        }
+
+        self.read_temperature_centi()
+    }
+
+    /// Take a single temperature measurement in degrees celcius.
+    ///
+    /// See [`Sensor::measure_once_centi`].
+    #[cfg(feature = "float")]
+    pub fn measure_once<D: DelayNs>(&mut self, delay: &mut D) -> Result<f32, Error<I2C::Error>> {
+        Ok(centi_to_celcius(self.measure_once_centi(delay)?))
+    }
 
-        Ok(composite * 0.01)
+    /// Read every register of the sensor.
+    ///
+    /// Reading the status register clears the limit flags, see
+    /// [`Sensor::read_status`]. Use [`Diagnostics::issues`] to check the
+    /// snapshot for inconsistencies.
+    pub fn diagnostics(&mut self) -> Result<Diagnostics, Error<I2C::Error>> {
+        Ok(Diagnostics {
+            device_id: self.read_reg()?,
+            high_limit: self.read_reg()?,
+            low_limit: self.read_reg()?,
+            control: self.read_reg()?,
+            status: self.read_reg()?,
+            temp_l: self.read_reg()?,
+            temp_h: self.read_reg()?,
+            soft_reset: self.read_reg()?,
+        })
     }
 
     /// Perform a software reset of the sensor.
     ///
-    /// Resets all digital blocks.
-    pub fn reset(&mut self) -> Result<(), I2C::Error> {
-        self.i2c.write(self.address + REG_SOFT_RESET, &[1 << 1])
+    /// Resets all digital blocks, waits for the sensor to come back and checks
+    /// the device ID. The operating mode and limits previously set through
+    /// this driver are restored afterwards.
+    pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Error<I2C::Error>> {
+        self.write_reg(SoftReset::default().with_sw_reset(true))?;
+        self.write_reg(SoftReset::default())?;
+
+        delay.delay_us(RESET_TIME_US);
+
+        self.check_device_id()?;
+
+        for (register, value) in self.config.restore() {
+            self.write_register(register, value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Recover from a stuck bus and re-initialise the sensor.
+    ///
+    /// A device interrupted mid-transfer, e.g. by an ESD event, can hold SDA
+    /// low so that every transaction fails. `bus_clear` is called first to
+    /// release the bus, typically by clocking SCL until SDA is released and
+    /// reinitialising the I²C peripheral. The sensor is then reset and its
+    /// configuration restored, see [`Sensor::reset`].
+    pub fn recover<F: FnOnce(&mut I2C), D: DelayNs>(
+        &mut self,
+        bus_clear: F,
+        delay: &mut D,
+    ) -> Result<(), Error<I2C::Error>> {
+        bus_clear(&mut self.i2c);
+
+        self.reset(delay)
+    }
+
+    /// Read a register.
+    pub fn read_reg<R: Register>(&mut self) -> Result<R, Error<I2C::Error>> {
+        Ok(R::from_bits(self.read_register(R::ADDRESS)?))
+    }
+
+    /// Write a register.
+    ///
+    /// Reserved bits are always written as zero. Block data update and
+    /// register address auto-increment are always set in the control
+    /// register, as burst reads rely on them.
+    pub fn write_reg<R: Writable>(&mut self, register: R) -> Result<(), Error<I2C::Error>> {
+        self.write_register(R::ADDRESS, register.bits() & !R::RESERVED)
+    }
+
+    /// Read a register, modify it and write it back.
+    pub fn modify_reg<R: Writable, F: FnOnce(R) -> R>(
+        &mut self,
+        f: F,
+    ) -> Result<(), Error<I2C::Error>> {
+        let register = self.read_reg::<R>()?;
+
+        self.write_reg(f(register))
+    }
+
+    /// Read a single register.
+    ///
+    /// The register address is written first, followed by a repeated start
+    /// and the read of the register contents.
+    fn read_register(&mut self, register: u8) -> Result<u8, Error<I2C::Error>> {
+        let mut buf: [u8; 1] = [0];
+
+        self.read_registers(register, &mut buf)?;
+
+        Ok(buf[0])
+    }
+
+    /// Read consecutive registers in a single transfer.
+    fn read_registers(&mut self, register: u8, buf: &mut [u8]) -> Result<(), Error<I2C::Error>> {
+        let mut attempt = 0;
+
+        loop {
+            match self.i2c.write_read(self.address, &[register], buf) {
+                Ok(()) => return Ok(()),
+                Err(e) if should_retry(&e, attempt, self.retries) => attempt += 1,
+                Err(e) => return Err(Error::I2c(e)),
+            }
+        }
+    }
+
+    /// Write a single register.
+    ///
+    /// The register address is sent as the first byte of the transfer.
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<I2C::Error>> {
+        let value = register_value(register, value);
+
+        let mut attempt = 0;
+
+        loop {
+            match self.i2c.write(self.address, &[register, value]) {
+                Ok(()) => break,
+                Err(e) if should_retry(&e, attempt, self.retries) => attempt += 1,
+                Err(e) => return Err(Error::I2c(e)),
+            }
+        }
+
+        self.config.record(register, value);
+
+        Ok(())
+    }
+}
+
+// The helpers below are shared by the blocking and the asynchronous driver,
+// which only differ in how they access the bus.
+
+/// Configuration written through a driver, restored after a reset
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Config {
+    control: Control,
+    high_limit: TempHighLimit,
+    low_limit: TempLowLimit,
+}
+
+impl Config {
+    /// Records a completed register write.
+    pub(crate) fn record(&mut self, register: u8, value: u8) {
+        match register {
+            TempHighLimit::ADDRESS => self.high_limit = TempHighLimit::from_bits(value),
+            TempLowLimit::ADDRESS => self.low_limit = TempLowLimit::from_bits(value),
+            // a single conversion returns to power down once complete
+            Control::ADDRESS => self.control = Control::from_bits(value).with_one_shot(false),
+            _ => {}
+        }
+    }
+
+    /// Register writes restoring the configuration, in order.
+    pub(crate) fn restore(&self) -> [(u8, u8); 3] {
+        [
+            (TempHighLimit::ADDRESS, self.high_limit.bits()),
+            (TempLowLimit::ADDRESS, self.low_limit.bits()),
+            (Control::ADDRESS, self.control.bits()),
+        ]
     }
 }
 
+/// Enables block data update and register address auto-increment.
+pub(crate) fn with_interface_bits(control: Control) -> Control {
+    control.with_bdu(true).with_if_add_inc(true)
+}
+
+/// Value written to a register.
+///
+/// The interface bits are always set in the control register, as burst reads
+/// rely on them.
+pub(crate) fn register_value(register: u8, value: u8) -> u8 {
+    match register {
+        Control::ADDRESS => with_interface_bits(Control::from_bits(value)).bits(),
+        _ => value,
+    }
+}
+
+/// Polling of a single conversion until it completes
+#[derive(Default)]
+pub(crate) struct ConversionPoll {
+    waited_us: u32,
+}
+
+impl ConversionPoll {
+    /// Time to wait before the next poll in microseconds, which is accounted
+    /// as waited.
+    pub(crate) fn interval_us(&mut self) -> u32 {
+        self.waited_us += CONVERSION_POLL_INTERVAL_US;
+
+        CONVERSION_POLL_INTERVAL_US
+    }
+
+    /// Whether the conversion did not complete in time.
+    pub(crate) fn timed_out(&self) -> bool {
+        self.waited_us >= CONVERSION_TIMEOUT_US
+    }
+}
+
+/// Converts a temperature in hundredths of degrees celcius into the required
+/// register value.
+///
+/// The register has a resolution of 0.64 °C with 0 °C at 63, values between
+/// steps are rounded down. See table 10 in the user manual for more details.
+///
+/// Returns `None` if the temperature cannot be represented.
+fn centi_temperature_to_reg_value(centi: i32) -> Option<u8> {
+    if !(TEMPERATURE_LIMIT_MIN_CENTI..=TEMPERATURE_LIMIT_MAX_CENTI).contains(&centi) {
+        return None;
+    }
+
+    Some((centi.div_euclid(64) + 63) as u8)
+}
+
 /// Converts a floating-point temperature into the required register value.
 ///
-/// See table 10 in the user manual for more details.
-fn temperature_to_reg_value(celcius: f32) -> u8 {
-    ((celcius / 0.64) + 63.0) as u8
+/// Returns `None` if the temperature cannot be represented.
+#[cfg(feature = "float")]
+fn temperature_to_reg_value(celcius: f32) -> Option<u8> {
+    if celcius.is_nan() {
+        return None;
+    }
+
+    centi_temperature_to_reg_value(celcius_to_centi(celcius))
+}
+
+/// Converts a limit register value into hundredths of degrees celcius.
+///
+/// Returns `None` for 0, which disables the limit.
+fn reg_value_to_centi_temperature(value: u8) -> Option<i32> {
+    match value {
+        0 => None,
+        value => Some((value as i32 - 63) * 64),
+    }
+}
+
+/// Converts a limit register value into degrees celcius.
+///
+/// Returns `None` for 0, which disables the limit.
+#[cfg(feature = "float")]
+fn reg_value_to_temperature(value: u8) -> Option<f32> {
+    reg_value_to_centi_temperature(value).map(|centi| centi as f32 * 0.01)
+}
+
+/// Converts degrees celcius into hundredths of degrees, rounding to nearest.
+#[cfg(feature = "float")]
+fn celcius_to_centi(celcius: f32) -> i32 {
+    let rounding = if celcius < 0.0 { -0.5 } else { 0.5 };
+
+    (celcius * 100.0 + rounding) as i32
+}
+
+/// Converts hundredths of degrees celcius into degrees.
+#[cfg(feature = "float")]
+fn centi_to_celcius(centi: i16) -> f32 {
+    centi as f32 * 0.01
+}
+
+/// Converts the data register contents into hundredths of degrees celcius.
+///
+/// The value is a two's complement number with the low byte first.
+fn raw_to_temperature(buf: [u8; 2]) -> i16 {
+    i16::from_le_bytes(buf)
 }
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
     use super::*;
+    use core::cell::RefCell;
+    use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+    use embedded_hal_bus::i2c::{CriticalSectionDevice, RefCellDevice};
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+    use std::vec;
+
+    /// Transactions of two sensors and another driver sharing a bus.
+    fn shared_bus_transactions() -> [I2cTransaction; 9] {
+        [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![0]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+            I2cTransaction::write_read(0x3F, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write_read(0x3F, vec![Control::ADDRESS], vec![0]),
+            I2cTransaction::write(0x3F, vec![Control::ADDRESS, 0b0100_1000]),
+            I2cTransaction::write(0x50, vec![0x00, 0x01]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+            I2cTransaction::write_read(0x3F, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+        ]
+    }
 
     #[test]
+    fn test_shared_bus_refcell() {
+        let bus = RefCell::new(I2cMock::new(&shared_bus_transactions()));
+
+        let mut high = Sensor::new_checked(RefCellDevice::new(&bus), AddressSelect::High).unwrap();
+        let mut low = Sensor::new_checked(RefCellDevice::new(&bus), AddressSelect::Low).unwrap();
+        let mut other = RefCellDevice::new(&bus);
+
+        other.write(0x50, &[0x00, 0x01]).unwrap();
+        assert_eq!(high.read_temperature_centi().unwrap(), 2500);
+        assert_eq!(low.read_temperature_centi().unwrap(), -1000);
+
+        high.release();
+        low.release();
+        bus.into_inner().done();
+    }
+
+    #[test]
+    fn test_reset_restores_configuration() {
+        let expectations = [
+            // configuration
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+            // reset
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.temperature_high_limit_centi(4000).unwrap();
+        sensor.configure(Mode::Continuous(Speed::Hz100)).unwrap();
+        sensor.reset(&mut NoopDelay).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_write_reg_clears_reserved_bits() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![0b0000_0010]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1100]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor
+            .modify_reg(|control: Control| control.with_freerun(true))
+            .unwrap();
+        sensor.write_reg(SoftReset::from_bits(0xFD)).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_new_checked() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            // the operating mode is kept
+            I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![0b0010_0100]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+            I2cTransaction::write_read(0x3F, vec![DeviceId::ADDRESS], vec![0x42]),
+        ];
+        let mut i2c = I2cMock::new(&expectations);
+
+        let sensor = Sensor::new_checked(i2c.clone(), AddressSelect::High).unwrap();
+        assert_eq!(sensor.address(), 0x38);
+        assert!(matches!(
+            Sensor::new_checked(i2c.clone(), AddressSelect::Low),
+            Err(Error::InvalidDeviceId(0x42))
+        ));
+
+        i2c.done();
+    }
+
+    #[test]
+    fn test_probe() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            I2cTransaction::write_read(0x3F, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write_read(0x3F, vec![Control::ADDRESS], vec![0]),
+            I2cTransaction::write(0x3F, vec![Control::ADDRESS, 0b0100_1000]),
+            // nothing on the bus
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            I2cTransaction::write_read(0x3F, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+        ];
+        let mut i2c = I2cMock::new(&expectations);
+
+        let sensor = Sensor::probe(i2c.clone()).ok().unwrap();
+        assert_eq!(sensor.address(), 0x3F);
+        assert!(Sensor::probe(i2c.clone()).is_err());
+
+        i2c.done();
+    }
+
+    #[test]
+    fn test_read_device_id() {
+        let expectations = [I2cTransaction::write_read(
+            0x3F,
+            vec![DeviceId::ADDRESS],
+            vec![DeviceId::EXPECTED],
+        )];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::Low);
+
+        assert_eq!(sensor.read_device_id().unwrap(), 0xA0);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_set_limits() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 1]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.temperature_high_limit_centi(4000).unwrap();
+        sensor.temperature_low_limit_centi(-3968).unwrap();
+        sensor.disable_temperature_high_limit().unwrap();
+        sensor.disable_temperature_low_limit().unwrap();
+
+        // out of range limits are rejected without a bus transaction
+        assert_eq!(
+            sensor.temperature_high_limit_centi(12289),
+            Err(Error::LimitOutOfRange)
+        );
+        assert_eq!(
+            sensor.temperature_low_limit_centi(-3969),
+            Err(Error::LimitOutOfRange)
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_set_float_limits() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 255]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 62]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.temperature_high_limit(122.88).unwrap();
+        sensor.temperature_low_limit(-0.64).unwrap();
+        assert_eq!(
+            sensor.temperature_high_limit(f32::NAN),
+            Err(Error::LimitOutOfRange)
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_read_limits() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![TempHighLimit::ADDRESS], vec![125]),
+            I2cTransaction::write_read(0x38, vec![TempLowLimit::ADDRESS], vec![0]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(
+            sensor.read_temperature_high_limit_centi().unwrap(),
+            Some(3968)
+        );
+        assert_eq!(sensor.read_temperature_low_limit_centi().unwrap(), None);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_read_float_limits() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![TempHighLimit::ADDRESS], vec![63]),
+            I2cTransaction::write_read(0x38, vec![TempLowLimit::ADDRESS], vec![0]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.read_temperature_high_limit().unwrap(), Some(0.0));
+        assert_eq!(sensor.read_temperature_low_limit().unwrap(), None);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_configure() {
+        let modes = [
+            (Mode::PowerDown, 0b0100_1000),
+            (Mode::SingleConversion, 0b0100_1001),
+            (Mode::Continuous(Speed::Hz25), 0b0100_1100),
+            (Mode::Continuous(Speed::Hz50), 0b0101_1100),
+            (Mode::Continuous(Speed::Hz100), 0b0110_1100),
+            (Mode::Continuous(Speed::Hz200), 0b0111_1100),
+            (Mode::LowOdr, 0b1100_1000),
+        ];
+
+        for (mode, control) in modes {
+            let expectations = [
+                I2cTransaction::write(0x38, vec![Control::ADDRESS, control]),
+                I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![control]),
+            ];
+            let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+            sensor.configure(mode).unwrap();
+            assert_eq!(sensor.read_configuration().unwrap(), mode);
+
+            sensor.release().done();
+        }
+    }
+
+    #[test]
+    fn test_averaging() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0111_1001]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b1111_1000]),
+            // continuous conversion keeps the averaging of its speed
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0101_1100]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.averaging(), Averaging::Max);
+        sensor.set_averaging(Averaging::Min);
+        sensor.configure(Mode::SingleConversion).unwrap();
+        sensor.configure(Mode::LowOdr).unwrap();
+        sensor.configure(Mode::Continuous(Speed::Hz50)).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_configure_profile() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b1111_1000]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0101_1100]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1100]),
+            // single conversion with the averaging of the last profile
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.configure_profile(Profile::LowPower).unwrap();
+        sensor.configure_profile(Profile::Balanced).unwrap();
+        sensor.configure_profile(Profile::LowNoise).unwrap();
+        sensor.configure(Mode::SingleConversion).unwrap();
+
+        sensor.release().done();
+
+        for profile in [Profile::Balanced, Profile::LowNoise] {
+            match profile.mode() {
+                Mode::Continuous(speed) => assert_eq!(Averaging::from(speed), profile.averaging()),
+                _ => unreachable!(),
+            }
+        }
+
+        // bounded by the conversion period of the matching speed
+        let conversion_times = [
+            (Averaging::Max, 40_000),
+            (Averaging::High, 20_000),
+            (Averaging::Low, 10_000),
+            (Averaging::Min, 5_000),
+        ];
+        for (averaging, time_us) in conversion_times {
+            assert_eq!(averaging.max_conversion_time_us(), time_us);
+        }
+    }
+
+    #[test]
+    fn test_read_temperature() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.read_temperature_centi().unwrap(), 2500);
+        assert_eq!(sensor.read_temperature_centi().unwrap(), -1000);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_read_float_temperature() {
+        let expectations = [I2cTransaction::write_read(
+            0x38,
+            vec![TempL::ADDRESS],
+            vec![0x18, 0xFC],
+        )];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.read_temperature().unwrap(), -10.0);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_calibrated_temperature() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x5A, 0x0A]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x5A, 0x0A]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let reading = sensor.read_temperature_centi().unwrap();
+        assert_eq!(reading, 2650);
+
+        sensor.set_calibration(Calibration::from_offset(reading, 2500));
+        assert_eq!(sensor.read_temperature_centi().unwrap(), 2500);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_read_status() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b110]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b001]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b000]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let status = sensor.read_status().unwrap();
+        assert!(!status.busy && status.over_high_limit && status.under_low_limit);
+        assert!(sensor.is_busy().unwrap());
+        assert!(!sensor.is_busy().unwrap());
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_measure_once() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b001]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.measure_once_centi(&mut NoopDelay).unwrap(), 2500);
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_measure_once_timeout() {
+        let mut expectations = vec![I2cTransaction::write(
+            0x38,
+            vec![Control::ADDRESS, 0b0100_1001],
+        )];
+        for _ in 0..CONVERSION_TIMEOUT_US / CONVERSION_POLL_INTERVAL_US {
+            expectations.push(I2cTransaction::write_read(
+                0x38,
+                vec![registers::Status::ADDRESS],
+                vec![0b001],
+            ));
+        }
+        // powered down after the timeout
+        expectations.push(I2cTransaction::write(
+            0x38,
+            vec![Control::ADDRESS, 0b0100_1000],
+        ));
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(
+            sensor.measure_once_centi(&mut NoopDelay),
+            Err(Error::Timeout)
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write_read(0x38, vec![TempHighLimit::ADDRESS], vec![125]),
+            I2cTransaction::write_read(0x38, vec![TempLowLimit::ADDRESS], vec![0]),
+            I2cTransaction::write_read(0x38, vec![Control::ADDRESS], vec![0b0100_1000]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b001]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4]),
+            I2cTransaction::write_read(0x38, vec![registers::TempH::ADDRESS], vec![0x09]),
+            I2cTransaction::write_read(0x38, vec![SoftReset::ADDRESS], vec![0]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let diagnostics = sensor.diagnostics().unwrap();
+        assert_eq!(diagnostics.high_limit.value(), 125);
+        assert_eq!(diagnostics.temperature_centi(), 2500);
+        assert_eq!(
+            diagnostics.issues(),
+            diagnostics::Issues {
+                stuck_busy: true,
+                ..Default::default()
+            }
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_reset_invalid_device_id() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0xFF]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        // the configuration is not restored to an unknown device
+        assert_eq!(
+            sensor.reset(&mut NoopDelay),
+            Err(Error::InvalidDeviceId(0xFF))
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_bus_error_propagation() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::Bus),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0])
+                .with_error(ErrorKind::Other),
+            // measurement fails while polling
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0])
+                .with_error(ErrorKind::Other),
+            // reset fails on the first write
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010])
+                .with_error(ErrorKind::Other),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert_eq!(sensor.read_device_id(), Err(Error::I2c(ErrorKind::Other)));
+        assert_eq!(
+            sensor.temperature_high_limit_centi(4000),
+            Err(Error::I2c(ErrorKind::Other))
+        );
+        assert_eq!(
+            sensor.configure(Mode::PowerDown),
+            Err(Error::I2c(ErrorKind::ArbitrationLoss))
+        );
+        assert_eq!(
+            sensor.read_temperature_centi(),
+            Err(Error::I2c(ErrorKind::Bus))
+        );
+        assert_eq!(sensor.read_status(), Err(Error::I2c(ErrorKind::Other)));
+        assert_eq!(
+            sensor.measure_once_centi(&mut NoopDelay),
+            Err(Error::I2c(ErrorKind::Other))
+        );
+        assert_eq!(
+            sensor.reset(&mut NoopDelay),
+            Err(Error::I2c(ErrorKind::Other))
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_bus_fault() {
+        let nack = Error::I2c(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+        assert_eq!(nack.bus_fault(), Some(BusFault::NoAcknowledge));
+        assert_eq!(
+            Error::I2c(ErrorKind::ArbitrationLoss).bus_fault(),
+            Some(BusFault::ArbitrationLoss)
+        );
+        assert_eq!(Error::I2c(ErrorKind::Bus).bus_fault(), Some(BusFault::Bus));
+        assert_eq!(
+            Error::I2c(ErrorKind::Overrun).bus_fault(),
+            Some(BusFault::Other)
+        );
+        assert_eq!(Error::<ErrorKind>::Timeout.bus_fault(), None);
+    }
+
+    #[test]
+    fn test_retries() {
+        let expectations = [
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![0])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63]),
+            // retries exhausted
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            // not retried
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::Bus),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63])
+                .with_error(ErrorKind::Other),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.set_retries(2);
+        assert_eq!(sensor.read_device_id().unwrap(), DeviceId::EXPECTED);
+        sensor.temperature_low_limit_centi(0).unwrap();
+        assert_eq!(
+            sensor.read_temperature_centi(),
+            Err(Error::I2c(ErrorKind::NoAcknowledge(
+                NoAcknowledgeSource::Address
+            )))
+        );
+        assert_eq!(
+            sensor.read_temperature_centi(),
+            Err(Error::I2c(ErrorKind::Bus))
+        );
+        assert_eq!(
+            sensor.temperature_low_limit_centi(0),
+            Err(Error::I2c(ErrorKind::Other))
+        );
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_shared_helpers() {
+        assert_eq!(register_value(Control::ADDRESS, 0b0000_0001), 0b0100_1001);
+        assert_eq!(register_value(TempHighLimit::ADDRESS, 0), 0);
+
+        let mut config = Config::default();
+        config.record(TempHighLimit::ADDRESS, 125);
+        config.record(Control::ADDRESS, 0b0100_1001);
+        config.record(SoftReset::ADDRESS, 0b0000_0010);
+        // the single conversion is not repeated
+        assert_eq!(
+            config.restore(),
+            [
+                (TempHighLimit::ADDRESS, 125),
+                (TempLowLimit::ADDRESS, 0),
+                (Control::ADDRESS, 0b0100_1000)
+            ]
+        );
+
+        let mut poll = ConversionPoll::default();
+        for _ in 0..CONVERSION_TIMEOUT_US / CONVERSION_POLL_INTERVAL_US {
+            assert!(!poll.timed_out());
+            assert_eq!(poll.interval_us(), CONVERSION_POLL_INTERVAL_US);
+        }
+        assert!(poll.timed_out());
+    }
+
+    #[test]
+    fn test_recover() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0, 0])
+                .with_error(ErrorKind::Bus),
+            // bus clear
+            I2cTransaction::write(0x00, vec![]),
+            // reset and restore
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        sensor.configure(Mode::Continuous(Speed::Hz100)).unwrap();
+        let error = sensor.read_temperature_centi().unwrap_err();
+        assert_eq!(error.bus_fault(), Some(BusFault::Bus));
+        assert!(!BusFault::Bus.is_transient());
+
+        sensor
+            .recover(|i2c| i2c.write(0x00, &[]).unwrap(), &mut NoopDelay)
+            .unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_failed_write_is_not_restored() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125])
+                .with_error(ErrorKind::Other),
+            // reset
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0010]),
+            I2cTransaction::write(0x38, vec![SoftReset::ADDRESS, 0b0000_0000]),
+            I2cTransaction::write_read(0x38, vec![DeviceId::ADDRESS], vec![DeviceId::EXPECTED]),
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 0]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        assert!(sensor.temperature_high_limit_centi(4000).is_err());
+        sensor.reset(&mut NoopDelay).unwrap();
+
+        sensor.release().done();
+    }
+
+    #[test]
+    fn test_shared_bus_critical_section() {
+        let bus =
+            critical_section::Mutex::new(RefCell::new(I2cMock::new(&shared_bus_transactions())));
+
+        let mut high =
+            Sensor::new_checked(CriticalSectionDevice::new(&bus), AddressSelect::High).unwrap();
+        let mut low =
+            Sensor::new_checked(CriticalSectionDevice::new(&bus), AddressSelect::Low).unwrap();
+        let mut other = CriticalSectionDevice::new(&bus);
+
+        other.write(0x50, &[0x00, 0x01]).unwrap();
+        assert_eq!(high.read_temperature_centi().unwrap(), 2500);
+        assert_eq!(low.read_temperature_centi().unwrap(), -1000);
+
+        high.release();
+        low.release();
+        bus.into_inner().into_inner().done();
+    }
+
+    #[test]
+    fn test_address_select() {
+        // SAO high selects 0x38, SAO low selects 0x3F
+        assert_eq!(SevenBitAddress::from(AddressSelect::High), 0x38);
+        assert_eq!(SevenBitAddress::from(AddressSelect::Low), 0x3F);
+
+        assert_eq!(AddressSelect::try_from(0x38), Ok(AddressSelect::High));
+        assert_eq!(AddressSelect::try_from(0x3F), Ok(AddressSelect::Low));
+        assert_eq!(AddressSelect::try_from(0x3C), Err(0x3C));
+    }
+
+    #[test]
+    fn test_centi_temperature_conversion() {
+        assert_eq!(centi_temperature_to_reg_value(-3968), Some(1));
+        assert_eq!(centi_temperature_to_reg_value(-3904), Some(2));
+        assert_eq!(centi_temperature_to_reg_value(-3903), Some(2));
+        assert_eq!(centi_temperature_to_reg_value(-3840), Some(3));
+        // ...
+        assert_eq!(centi_temperature_to_reg_value(-64), Some(62));
+        assert_eq!(centi_temperature_to_reg_value(-1), Some(62));
+        assert_eq!(centi_temperature_to_reg_value(0), Some(63));
+        assert_eq!(centi_temperature_to_reg_value(63), Some(63));
+        assert_eq!(centi_temperature_to_reg_value(64), Some(64));
+        // ...
+        assert_eq!(centi_temperature_to_reg_value(12224), Some(254));
+        assert_eq!(centi_temperature_to_reg_value(12288), Some(255));
+        // out of range
+        assert_eq!(centi_temperature_to_reg_value(-3969), None);
+        assert_eq!(centi_temperature_to_reg_value(12289), None);
+        assert_eq!(centi_temperature_to_reg_value(i32::MIN), None);
+        assert_eq!(centi_temperature_to_reg_value(i32::MAX), None);
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
     fn test_temperature_conversion() {
         // examples copied from table 10 in reference manual
         // rounded towards zero by 0.001 to work properly for some cases
-        assert_eq!(temperature_to_reg_value(-39.68), 1);
-        assert_eq!(temperature_to_reg_value(-39.04 + 0.001), 2);
-        assert_eq!(temperature_to_reg_value(-38.40 + 0.001), 3);
+        assert_eq!(temperature_to_reg_value(-39.68), Some(1));
+        assert_eq!(temperature_to_reg_value(-39.04 + 0.001), Some(2));
+        assert_eq!(temperature_to_reg_value(-38.40 + 0.001), Some(3));
         // ...
-        assert_eq!(temperature_to_reg_value(-0.64), 62);
-        assert_eq!(temperature_to_reg_value(0.0), 63);
-        assert_eq!(temperature_to_reg_value(0.64), 64);
+        assert_eq!(temperature_to_reg_value(-0.64), Some(62));
+        assert_eq!(temperature_to_reg_value(0.0), Some(63));
+        assert_eq!(temperature_to_reg_value(0.64), Some(64));
         // ...
-        assert_eq!(temperature_to_reg_value(122.24), 254);
-        assert_eq!(temperature_to_reg_value(122.88), 255);
+        assert_eq!(temperature_to_reg_value(122.24), Some(254));
+        assert_eq!(temperature_to_reg_value(122.88), Some(255));
+        // out of range
+        assert_eq!(temperature_to_reg_value(-39.69), None);
+        assert_eq!(temperature_to_reg_value(122.89), None);
+        assert_eq!(temperature_to_reg_value(f32::NAN), None);
+        assert_eq!(temperature_to_reg_value(f32::INFINITY), None);
+        assert_eq!(temperature_to_reg_value(f32::NEG_INFINITY), None);
+    }
+
+    #[test]
+    fn test_limit_round_trip() {
+        assert_eq!(reg_value_to_centi_temperature(0), None);
+        assert_eq!(reg_value_to_centi_temperature(1), Some(-3968));
+        assert_eq!(reg_value_to_centi_temperature(63), Some(0));
+        assert_eq!(reg_value_to_centi_temperature(255), Some(12288));
+
+        for value in 1..=255 {
+            let centi = reg_value_to_centi_temperature(value).unwrap();
+            assert_eq!(centi_temperature_to_reg_value(centi), Some(value));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "float")]
+    fn test_float_limit_round_trip() {
+        assert_eq!(reg_value_to_temperature(0), None);
+
+        for value in 1..=255 {
+            let celcius = reg_value_to_temperature(value).unwrap();
+            assert_eq!(temperature_to_reg_value(celcius), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_raw_temperature_conversion() {
+        assert_eq!(raw_to_temperature([0x00, 0x00]), 0);
+        assert_eq!(raw_to_temperature([0xC4, 0x09]), 2500);
+        assert_eq!(raw_to_temperature([0xFF, 0xFF]), -1);
+        assert_eq!(raw_to_temperature([0x18, 0xFC]), -1000);
+        assert_eq!(raw_to_temperature([0x80, 0xF0]), -3968);
+    }
+
+    #[test]
+    fn test_status_decoding() {
+        assert_eq!(
+            Status::from(0b000),
+            Status {
+                busy: false,
+                over_high_limit: false,
+                under_low_limit: false,
+            }
+        );
+        assert!(Status::from(0b001).busy);
+        assert!(Status::from(0b010).over_high_limit);
+        assert!(Status::from(0b100).under_low_limit);
+    }
+
+    #[test]
+    fn test_control_encoding() {
+        assert_eq!(Mode::PowerDown.to_control().bits(), 0b0000_0000);
+        assert_eq!(Mode::SingleConversion.to_control().bits(), 0b0000_0001);
+        assert_eq!(
+            Mode::Continuous(Speed::Hz25).to_control().bits(),
+            0b0000_0100
+        );
+        assert_eq!(
+            Mode::Continuous(Speed::Hz50).to_control().bits(),
+            0b0001_0100
+        );
+        assert_eq!(
+            Mode::Continuous(Speed::Hz100).to_control().bits(),
+            0b0010_0100
+        );
+        assert_eq!(
+            Mode::Continuous(Speed::Hz200).to_control().bits(),
+            0b0011_0100
+        );
+        assert_eq!(Mode::LowOdr.to_control().bits(), 0b1000_0000);
+
+        let modes = [
+            Mode::PowerDown,
+            Mode::SingleConversion,
+            Mode::Continuous(Speed::Hz25),
+            Mode::Continuous(Speed::Hz50),
+            Mode::Continuous(Speed::Hz100),
+            Mode::Continuous(Speed::Hz200),
+            Mode::LowOdr,
+        ];
+
+        for mode in modes {
+            assert_eq!(Mode::from_control(mode.to_control()), mode);
+            // interface bits must not affect the decoded mode
+            let control = mode.to_control().with_bdu(true).with_if_add_inc(true);
+            assert_eq!(Mode::from_control(control), mode);
+        }
     }
 }
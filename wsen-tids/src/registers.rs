@@ -0,0 +1,300 @@
+//! Typed register map.
+//!
+//! Every register is described by a bitfield struct implementing [`Register`].
+//! Reserved bits are preserved when read, so they can be inspected, but are
+//! always written as zero.
+
+use crate::{Averaging, Speed};
+
+/// A register of the sensor.
+pub trait Register: Copy {
+    /// Register address.
+    const ADDRESS: u8;
+
+    /// Reserved bits, which must be written as zero.
+    const RESERVED: u8;
+
+    /// Creates the register from its raw contents.
+    fn from_bits(bits: u8) -> Self;
+
+    /// Raw register contents.
+    fn bits(self) -> u8;
+
+    /// Reserved bits that are set.
+    fn reserved_bits(self) -> u8 {
+        self.bits() & Self::RESERVED
+    }
+}
+
+/// A register that can be written.
+pub trait Writable: Register {}
+
+macro_rules! register {
+    ($name:ident, $address:expr, $reserved:expr) => {
+        impl Register for $name {
+            const ADDRESS: u8 = $address;
+            const RESERVED: u8 = $reserved;
+
+            fn from_bits(bits: u8) -> Self {
+                Self(bits)
+            }
+
+            fn bits(self) -> u8 {
+                self.0
+            }
+        }
+    };
+}
+
+/// Sets or clears the bits in `mask`.
+fn set_bits(bits: u8, mask: u8, value: bool) -> u8 {
+    if value {
+        bits | mask
+    } else {
+        bits & !mask
+    }
+}
+
+/// Device ID register (read only)
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DeviceId(u8);
+
+register!(DeviceId, 0x01, 0);
+
+impl DeviceId {
+    /// Contents of the register on a WSEN-TIDS.
+    pub const EXPECTED: u8 = 0xA0;
+
+    /// Device ID.
+    pub fn id(self) -> u8 {
+        self.0
+    }
+}
+
+/// High temperature limit register
+///
+/// The limit is encoded with 0.64 °C steps and 0 °C at 63. A value of 0
+/// disables the limit.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TempHighLimit(u8);
+
+register!(TempHighLimit, 0x02, 0);
+impl Writable for TempHighLimit {}
+
+impl TempHighLimit {
+    /// Creates the register from the encoded limit.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Encoded limit.
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Whether the limit generates interrupts.
+    pub fn is_enabled(self) -> bool {
+        self.0 != 0
+    }
+}
+
+/// Low temperature limit register
+///
+/// The limit is encoded with 0.64 °C steps and 0 °C at 63. A value of 0
+/// disables the limit.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TempLowLimit(u8);
+
+register!(TempLowLimit, 0x03, 0);
+impl Writable for TempLowLimit {}
+
+impl TempLowLimit {
+    /// Creates the register from the encoded limit.
+    pub fn new(value: u8) -> Self {
+        Self(value)
+    }
+
+    /// Encoded limit.
+    pub fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Whether the limit generates interrupts.
+    pub fn is_enabled(self) -> bool {
+        self.0 != 0
+    }
+}
+
+/// Control register
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Control(u8);
+
+register!(Control, 0x04, 1 << 1);
+impl Writable for Control {}
+
+impl Control {
+    pub(crate) const ONE_SHOT: u8 = 1 << 0;
+    const FREERUN: u8 = 1 << 2;
+    const IF_ADD_INC: u8 = 1 << 3;
+    const FREQ_SHIFT: u8 = 4;
+    const FREQ_MASK: u8 = 0b11 << Self::FREQ_SHIFT;
+    const BDU: u8 = 1 << 6;
+    const LOW_ODR_START: u8 = 1 << 7;
+
+    /// Single conversion trigger, cleared once the conversion is complete.
+    pub fn one_shot(self) -> bool {
+        self.0 & Self::ONE_SHOT != 0
+    }
+
+    /// Sets the single conversion trigger, see [`Control::one_shot`].
+    pub fn with_one_shot(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::ONE_SHOT, value))
+    }
+
+    /// Continuous conversion.
+    pub fn freerun(self) -> bool {
+        self.0 & Self::FREERUN != 0
+    }
+
+    /// Sets continuous conversion, see [`Control::freerun`].
+    pub fn with_freerun(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::FREERUN, value))
+    }
+
+    /// Register address auto-increment for multi-byte transfers.
+    pub fn if_add_inc(self) -> bool {
+        self.0 & Self::IF_ADD_INC != 0
+    }
+
+    /// Sets register address auto-increment, see [`Control::if_add_inc`].
+    pub fn with_if_add_inc(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::IF_ADD_INC, value))
+    }
+
+    /// Continuous conversion speed.
+    pub fn freq(self) -> Speed {
+        Speed::from_bits((self.0 & Self::FREQ_MASK) >> Self::FREQ_SHIFT)
+    }
+
+    /// Sets the continuous conversion speed, see [`Control::freq`].
+    pub fn with_freq(self, speed: Speed) -> Self {
+        Self((self.0 & !Self::FREQ_MASK) | (speed as u8) << Self::FREQ_SHIFT)
+    }
+
+    /// Averaging of single and low output data rate conversions.
+    ///
+    /// Shares the bits with [`Control::freq`].
+    pub fn avg(self) -> Averaging {
+        Averaging::from_bits((self.0 & Self::FREQ_MASK) >> Self::FREQ_SHIFT)
+    }
+
+    /// Sets the averaging, see [`Control::avg`].
+    pub fn with_avg(self, averaging: Averaging) -> Self {
+        Self((self.0 & !Self::FREQ_MASK) | (averaging as u8) << Self::FREQ_SHIFT)
+    }
+
+    /// Block data update, the data registers are not updated until both have
+    /// been read.
+    pub fn bdu(self) -> bool {
+        self.0 & Self::BDU != 0
+    }
+
+    /// Sets block data update, see [`Control::bdu`].
+    pub fn with_bdu(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::BDU, value))
+    }
+
+    /// Continuous conversion at the 1 Hz low output data rate.
+    pub fn low_odr_start(self) -> bool {
+        self.0 & Self::LOW_ODR_START != 0
+    }
+
+    /// Sets the low output data rate, see [`Control::low_odr_start`].
+    pub fn with_low_odr_start(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::LOW_ODR_START, value))
+    }
+}
+
+/// Status register (read only)
+///
+/// The limit flags are cleared when the register is read.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Status(u8);
+
+register!(Status, 0x05, 0b1111_1000);
+
+impl Status {
+    pub(crate) const BUSY: u8 = 1 << 0;
+    pub(crate) const OVER_THL: u8 = 1 << 1;
+    pub(crate) const UNDER_TLL: u8 = 1 << 2;
+
+    /// A conversion is in progress.
+    pub fn busy(self) -> bool {
+        self.0 & Self::BUSY != 0
+    }
+
+    /// The temperature exceeded the high limit.
+    pub fn over_thl(self) -> bool {
+        self.0 & Self::OVER_THL != 0
+    }
+
+    /// The temperature dropped below the low limit.
+    pub fn under_tll(self) -> bool {
+        self.0 & Self::UNDER_TLL != 0
+    }
+}
+
+/// Temperature data low byte (read only)
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TempL(u8);
+
+register!(TempL, 0x06, 0);
+
+impl TempL {
+    /// Low byte of the two's complement temperature.
+    pub fn value(self) -> u8 {
+        self.0
+    }
+}
+
+/// Temperature data high byte (read only)
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TempH(u8);
+
+register!(TempH, 0x07, 0);
+
+impl TempH {
+    /// High byte of the two's complement temperature.
+    pub fn value(self) -> u8 {
+        self.0
+    }
+}
+
+/// Software reset register
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SoftReset(u8);
+
+register!(SoftReset, 0x0C, !(1 << 1));
+impl Writable for SoftReset {}
+
+impl SoftReset {
+    const SW_RESET: u8 = 1 << 1;
+
+    /// Software reset of all digital blocks.
+    pub fn sw_reset(self) -> bool {
+        self.0 & Self::SW_RESET != 0
+    }
+
+    /// Sets the software reset, see [`SoftReset::sw_reset`].
+    pub fn with_sw_reset(self, value: bool) -> Self {
+        Self(set_bits(self.0, Self::SW_RESET, value))
+    }
+}
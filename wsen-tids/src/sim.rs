@@ -0,0 +1,338 @@
+//! Register level simulation of the sensor for testing without hardware.
+//!
+//! [`SimulatedTids`] implements the blocking I²C traits and can be passed to
+//! [`crate::Sensor`] in place of a real bus. Temperatures are produced by a
+//! user supplied profile, called with the index of each conversion.
+//!
+//! Time is modelled by bus transactions: in continuous mode every transaction
+//! addressed to the sensor is preceded by one conversion. A single conversion
+//! stays busy until the status register has been read once.
+
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+use crate::{
+    reg_value_to_centi_temperature,
+    registers::{
+        Control, DeviceId, Register, SoftReset, Status, TempH, TempHighLimit, TempL, TempLowLimit,
+    },
+    AddressSelect,
+};
+
+// number of registers in the map
+const REGISTER_COUNT: usize = SoftReset::ADDRESS as usize + 1;
+
+/// Simulated sensor on an I²C bus.
+pub struct SimulatedTids<P> {
+    address: u8,
+    registers: [u8; REGISTER_COUNT],
+    pointer: u8,
+    profile: P,
+    conversions: u32,
+}
+
+// the profile is usually a closure, which cannot be formatted
+impl<P> core::fmt::Debug for SimulatedTids<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SimulatedTids")
+            .field("address", &self.address)
+            .field("registers", &self.registers)
+            .field("pointer", &self.pointer)
+            .field("conversions", &self.conversions)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P: FnMut(u32) -> i16> SimulatedTids<P> {
+    /// Creates a simulated sensor at the given address.
+    ///
+    /// The profile returns the temperature in hundredths of degrees celcius
+    /// for each conversion.
+    pub fn new(address: AddressSelect, profile: P) -> Self {
+        let mut sim = Self {
+            address: address.into(),
+            registers: [0; REGISTER_COUNT],
+            pointer: 0,
+            profile,
+            conversions: 0,
+        };
+
+        sim.reset_registers();
+
+        sim
+    }
+
+    /// Contents of a register, without side effects.
+    pub fn register(&self, address: u8) -> u8 {
+        self.registers.get(address as usize).copied().unwrap_or(0)
+    }
+
+    /// Number of conversions performed.
+    pub fn conversions(&self) -> u32 {
+        self.conversions
+    }
+
+    /// Whether the (active low) interrupt pin is asserted.
+    pub fn interrupt(&self) -> bool {
+        let status = Status::from_bits(self.registers[Status::ADDRESS as usize]);
+
+        status.over_thl() || status.under_tll()
+    }
+
+    /// Advance time by one conversion period.
+    pub fn step(&mut self) {
+        let control = self.control();
+
+        if control.freerun() || control.low_odr_start() {
+            self.convert();
+        }
+    }
+
+    fn control(&self) -> Control {
+        Control::from_bits(self.registers[Control::ADDRESS as usize])
+    }
+
+    fn reset_registers(&mut self) {
+        self.registers = [0; REGISTER_COUNT];
+        self.registers[DeviceId::ADDRESS as usize] = DeviceId::EXPECTED;
+    }
+
+    /// Performs a conversion and evaluates the limits.
+    fn convert(&mut self) {
+        let centi = (self.profile)(self.conversions);
+        self.conversions = self.conversions.wrapping_add(1);
+
+        let [low, high] = centi.to_le_bytes();
+        self.registers[TempL::ADDRESS as usize] = low;
+        self.registers[TempH::ADDRESS as usize] = high;
+
+        let high_limit = reg_value_to_centi_temperature(self.register(TempHighLimit::ADDRESS));
+        if high_limit.is_some_and(|limit| centi as i32 > limit) {
+            self.registers[Status::ADDRESS as usize] |= Status::OVER_THL;
+        }
+
+        let low_limit = reg_value_to_centi_temperature(self.register(TempLowLimit::ADDRESS));
+        if low_limit.is_some_and(|limit| (centi as i32) < limit) {
+            self.registers[Status::ADDRESS as usize] |= Status::UNDER_TLL;
+        }
+    }
+
+    fn read_byte(&mut self) -> u8 {
+        let address = self.pointer;
+        let value = self.register(address);
+
+        if address == Status::ADDRESS {
+            // reading the status clears the limit flags
+            self.registers[address as usize] = 0;
+
+            // a pending single conversion completes after the busy flag was seen
+            if Status::from_bits(value).busy() {
+                self.registers[Control::ADDRESS as usize] &= !Control::ONE_SHOT;
+                self.convert();
+            }
+        }
+
+        self.advance_pointer();
+
+        value
+    }
+
+    fn write_byte(&mut self, value: u8) {
+        let address = self.pointer;
+
+        match address {
+            TempHighLimit::ADDRESS | TempLowLimit::ADDRESS => {
+                self.registers[address as usize] = value;
+            }
+            Control::ADDRESS => {
+                let value = value & !Control::RESERVED;
+                self.registers[address as usize] = value;
+
+                if Control::from_bits(value).one_shot() {
+                    self.registers[Status::ADDRESS as usize] |= Status::BUSY;
+                }
+            }
+            SoftReset::ADDRESS => {
+                if SoftReset::from_bits(value).sw_reset() {
+                    self.reset_registers();
+                }
+
+                self.registers[address as usize] = value & !SoftReset::RESERVED;
+            }
+            // read only or unmapped
+            _ => {}
+        }
+
+        self.advance_pointer();
+    }
+
+    fn advance_pointer(&mut self) {
+        if self.control().if_add_inc() {
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+}
+
+impl<P> ErrorType for SimulatedTids<P> {
+    type Error = ErrorKind;
+}
+
+impl<P: FnMut(u32) -> i16> I2c for SimulatedTids<P> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+
+        self.step();
+
+        let mut pointer_set = false;
+
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => {
+                    for &byte in bytes.iter() {
+                        if pointer_set {
+                            self.write_byte(byte);
+                        } else {
+                            self.pointer = byte;
+                            pointer_set = true;
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    for byte in buf.iter_mut() {
+                        *byte = self.read_byte();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{Error, Mode, Sensor, Speed};
+    use embedded_hal_mock::eh1::delay::NoopDelay;
+    use std::format;
+
+    #[test]
+    fn test_device_id_and_wrong_address() {
+        let sim = SimulatedTids::new(AddressSelect::Low, |_| 0);
+        let mut sensor = Sensor::new_checked(sim, AddressSelect::Low).unwrap();
+        assert_eq!(sensor.read_device_id().unwrap(), 0xA0);
+
+        let sim = sensor.release();
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+        assert!(matches!(
+            sensor.read_device_id(),
+            Err(Error::I2c(ErrorKind::NoAcknowledge(_)))
+        ));
+    }
+
+    #[test]
+    fn test_debug() {
+        let sim = SimulatedTids::new(AddressSelect::High, |_| 0);
+        let sensor = Sensor::new(sim, AddressSelect::High);
+
+        let debug = format!("{sensor:?}");
+        assert!(debug.starts_with("Sensor { i2c: SimulatedTids { address: 56,"));
+        assert!(debug.contains("control: Control(0)"));
+    }
+
+    #[test]
+    fn test_single_conversion() {
+        let sim = SimulatedTids::new(AddressSelect::High, |_| -1234);
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+
+        assert_eq!(sensor.measure_once_centi(&mut NoopDelay).unwrap(), -1234);
+        assert_eq!(sensor.read_configuration().unwrap(), Mode::PowerDown);
+        assert_eq!(sensor.release().conversions(), 1);
+    }
+
+    #[test]
+    fn test_continuous_limits() {
+        let sim = SimulatedTids::new(AddressSelect::High, |n| n as i16 * 2000);
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+
+        sensor.temperature_high_limit_centi(2500).unwrap();
+        sensor.temperature_low_limit_centi(500).unwrap();
+        sensor.configure(Mode::Continuous(Speed::Hz25)).unwrap();
+
+        // first conversion is 0 °C
+        let status = sensor.read_status().unwrap();
+        assert!(status.under_low_limit && !status.over_high_limit);
+
+        // then 20 °C and 40 °C
+        assert_eq!(sensor.read_temperature_centi().unwrap(), 2000);
+        let status = sensor.read_status().unwrap();
+        assert!(status.over_high_limit);
+
+        // flags are cleared by reading
+        let sim = sensor.release();
+        assert!(!sim.interrupt());
+    }
+
+    #[test]
+    fn test_soft_reset() {
+        let sim = SimulatedTids::new(AddressSelect::High, |_| 0);
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+
+        sensor.configure(Mode::Continuous(Speed::Hz50)).unwrap();
+        sensor.temperature_high_limit_centi(4000).unwrap();
+        sensor.reset(&mut NoopDelay).unwrap();
+
+        assert_eq!(
+            sensor.read_configuration().unwrap(),
+            Mode::Continuous(Speed::Hz50)
+        );
+        assert_eq!(
+            sensor.read_temperature_high_limit_centi().unwrap(),
+            Some(3968)
+        );
+        assert_eq!(sensor.release().register(SoftReset::ADDRESS), 0);
+    }
+
+    #[test]
+    fn test_read_without_configure() {
+        let sim = SimulatedTids::new(AddressSelect::High, |_| -1000);
+        let mut sensor = Sensor::new_checked(sim, AddressSelect::High).unwrap();
+
+        sensor
+            .modify_reg(|control: Control| control.with_one_shot(true))
+            .unwrap();
+        assert!(sensor.read_status().unwrap().busy);
+        assert_eq!(sensor.read_temperature_centi().unwrap(), -1000);
+
+        // without initialisation
+        let sim = SimulatedTids::new(AddressSelect::High, |_| 2500);
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+
+        sensor
+            .write_reg(Control::default().with_one_shot(true))
+            .unwrap();
+        assert!(sensor.read_status().unwrap().busy);
+        assert_eq!(sensor.read_temperature_centi().unwrap(), 2500);
+    }
+
+    #[test]
+    fn test_reset_without_configure() {
+        let sim = SimulatedTids::new(AddressSelect::High, |_| -1000);
+        let mut sensor = Sensor::new(sim, AddressSelect::High);
+
+        sensor.reset(&mut NoopDelay).unwrap();
+        sensor
+            .modify_reg(|control: Control| control.with_one_shot(true))
+            .unwrap();
+
+        assert!(sensor.read_status().unwrap().busy);
+        assert_eq!(sensor.read_temperature_centi().unwrap(), -1000);
+    }
+}
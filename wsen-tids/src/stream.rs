@@ -0,0 +1,379 @@
+//! Continuous streaming of temperature samples into a ring buffer.
+//!
+//! The sensor has no data-ready output, the interrupt pin only signals limit
+//! crossings. [`StreamReader::tick`] is therefore meant to be called from a
+//! periodic timer, at most once per [`Speed::period_us`], and reads the latest
+//! sample into a fixed-capacity [`RingBuffer`]. The consumer drains the
+//! buffer at its own pace, e.g. from the main loop.
+
+use core::fmt;
+
+use embedded_hal::i2c::I2c;
+
+use crate::{Error, Mode, Sensor, Speed};
+
+/// Timestamped temperature sample
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Sample {
+    /// Timestamp passed to [`StreamReader::tick`].
+    pub timestamp: u64,
+    /// Temperature in hundredths of degrees celcius.
+    pub centi: i16,
+}
+
+/// Fixed-capacity ring buffer of samples
+///
+/// When full, pushing a sample overwrites the oldest one and counts an
+/// overrun.
+///
+/// Buffers compare equal if they hold the same samples in the same order and
+/// counted the same overruns, regardless of their history.
+#[derive(Clone)]
+pub struct RingBuffer<const N: usize> {
+    samples: [Sample; N],
+    head: usize,
+    len: usize,
+    overruns: u32,
+}
+
+impl<const N: usize> Default for RingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> RingBuffer<N> {
+    /// Creates an empty ring buffer.
+    pub const fn new() -> Self {
+        Self {
+            samples: [Sample {
+                timestamp: 0,
+                centi: 0,
+            }; N],
+            head: 0,
+            len: 0,
+            overruns: 0,
+        }
+    }
+
+    /// Maximum number of samples held.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Number of samples held.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Number of samples overwritten before they were read.
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Returns the overrun count and resets it.
+    pub fn take_overruns(&mut self) -> u32 {
+        core::mem::take(&mut self.overruns)
+    }
+
+    /// Appends a sample, overwriting the oldest one when full.
+    ///
+    /// Returns `false` if a sample was overwritten.
+    pub fn push(&mut self, sample: Sample) -> bool {
+        if N == 0 {
+            self.overruns = self.overruns.saturating_add(1);
+            return false;
+        }
+
+        let tail = (self.head + self.len) % N;
+        self.samples[tail] = sample;
+
+        if self.len == N {
+            self.head = (self.head + 1) % N;
+            self.overruns = self.overruns.saturating_add(1);
+            false
+        } else {
+            self.len += 1;
+            true
+        }
+    }
+
+    /// Removes and returns the oldest sample.
+    pub fn pop(&mut self) -> Option<Sample> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let sample = self.samples[self.head];
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+
+        Some(sample)
+    }
+
+    /// Removes all samples.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Iterates over the samples from oldest to newest without removing them.
+    pub fn iter(&self) -> impl Iterator<Item = &Sample> + '_ {
+        (0..self.len).map(move |i| &self.samples[(self.head + i) % N])
+    }
+}
+
+impl<const N: usize> PartialEq for RingBuffer<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.overruns == other.overruns && self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize> fmt::Debug for RingBuffer<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // only the samples held, not the stale slots
+        struct Samples<'a, const N: usize>(&'a RingBuffer<N>);
+
+        impl<const N: usize> fmt::Debug for Samples<'_, N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_list().entries(self.0.iter()).finish()
+            }
+        }
+
+        f.debug_struct("RingBuffer")
+            .field("samples", &Samples(self))
+            .field("overruns", &self.overruns)
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const N: usize> defmt::Format for RingBuffer<N> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "RingBuffer {{ samples: [");
+
+        for (i, sample) in self.iter().enumerate() {
+            if i > 0 {
+                defmt::write!(f, ", ");
+            }
+            defmt::write!(f, "{}", sample);
+        }
+
+        defmt::write!(f, "], overruns: {} }}", self.overruns);
+    }
+}
+
+/// Reads samples in continuous mode into a ring buffer of `N` samples.
+#[derive(Debug)]
+pub struct StreamReader<I2C, const N: usize> {
+    sensor: Sensor<I2C>,
+    speed: Speed,
+    buffer: RingBuffer<N>,
+}
+
+impl<I2C: I2c, const N: usize> StreamReader<I2C, N> {
+    /// Creates a new stream reader and starts continuous conversion.
+    ///
+    /// Returns the sensor together with the error on failure.
+    pub fn new(
+        mut sensor: Sensor<I2C>,
+        speed: Speed,
+    ) -> Result<Self, (Sensor<I2C>, Error<I2C::Error>)> {
+        match sensor.configure(Mode::Continuous(speed)) {
+            Ok(()) => Ok(Self {
+                sensor,
+                speed,
+                buffer: RingBuffer::new(),
+            }),
+            Err(e) => Err((sensor, e)),
+        }
+    }
+
+    /// Configured conversion speed.
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Reads the latest sample into the buffer.
+    ///
+    /// The timestamp is stored with the sample, in any unit the caller
+    /// chooses. If the buffer is full, the oldest sample is overwritten and
+    /// counted as an overrun.
+    ///
+    /// Returns `false` on an overrun, i.e. when the consumer fell behind.
+    pub fn tick(&mut self, timestamp: u64) -> Result<bool, Error<I2C::Error>> {
+        let centi = self.sensor.read_temperature_centi()?;
+
+        Ok(self.buffer.push(Sample { timestamp, centi }))
+    }
+
+    /// Removes and returns the oldest sample.
+    pub fn pop(&mut self) -> Option<Sample> {
+        self.buffer.pop()
+    }
+
+    /// Sample buffer.
+    pub fn buffer(&mut self) -> &mut RingBuffer<N> {
+        &mut self.buffer
+    }
+
+    /// Powers the sensor down and returns it.
+    ///
+    /// Returns the reader together with the error on failure.
+    pub fn stop(mut self) -> Result<Sensor<I2C>, (Self, Error<I2C::Error>)> {
+        match self.sensor.configure(Mode::PowerDown) {
+            Ok(()) => Ok(self.sensor),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Releases the sensor, leaving it converting.
+    pub fn release(self) -> Sensor<I2C> {
+        self.sensor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        registers::{Control, Register},
+        sim::SimulatedTids,
+        AddressSelect,
+    };
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::{format, vec};
+
+    fn sample(timestamp: u64) -> Sample {
+        Sample {
+            timestamp,
+            centi: timestamp as i16,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let mut buffer = RingBuffer::<3>::new();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.pop(), None);
+
+        assert!(buffer.push(sample(1)));
+        assert!(buffer.push(sample(2)));
+        assert_eq!(buffer.pop(), Some(sample(1)));
+        assert!(buffer.push(sample(3)));
+        assert!(buffer.push(sample(4)));
+        assert!(buffer.is_full());
+
+        // oldest sample is overwritten
+        assert!(!buffer.push(sample(5)));
+        assert_eq!(buffer.overruns(), 1);
+        assert_eq!(buffer.pop(), Some(sample(3)));
+        assert_eq!(buffer.pop(), Some(sample(4)));
+        assert_eq!(buffer.pop(), Some(sample(5)));
+        assert_eq!(buffer.pop(), None);
+
+        assert_eq!(buffer.take_overruns(), 1);
+        assert_eq!(buffer.overruns(), 0);
+
+        buffer.push(sample(6));
+        let copy = buffer.clone();
+        assert_eq!(copy, buffer);
+        buffer.clear();
+        assert_ne!(copy, buffer);
+    }
+
+    #[test]
+    fn test_ring_buffer_eq() {
+        // stale slots are not compared
+        let mut buffer = RingBuffer::<3>::new();
+        buffer.push(sample(1));
+        buffer.pop();
+        assert_eq!(buffer, RingBuffer::new());
+
+        // nor the position of the samples
+        buffer.push(sample(2));
+        let mut other = RingBuffer::<3>::new();
+        other.push(sample(2));
+        assert_eq!(buffer, other);
+        assert_eq!(format!("{buffer:?}"), format!("{other:?}"));
+        assert_eq!(
+            format!("{other:?}"),
+            "RingBuffer { samples: [Sample { timestamp: 2, centi: 2 }], overruns: 0 }"
+        );
+
+        other.push(sample(3));
+        assert_ne!(buffer, other);
+
+        // overruns are compared
+        let mut buffer = RingBuffer::<1>::new();
+        buffer.push(sample(1));
+        buffer.push(sample(2));
+        let mut other = RingBuffer::<1>::new();
+        other.push(sample(2));
+        assert_ne!(buffer, other);
+        other.push(sample(2));
+        assert_eq!(buffer, other);
+    }
+
+    #[test]
+    fn test_stream_reader() {
+        let sim = SimulatedTids::new(AddressSelect::High, |n| n as i16 * 10);
+        let sensor = Sensor::new(sim, AddressSelect::High);
+        let mut reader = StreamReader::<_, 4>::new(sensor, Speed::Hz200).unwrap();
+
+        for timestamp in 0..4 {
+            assert!(reader.tick(timestamp * 5).unwrap());
+        }
+        // the consumer fell behind
+        for timestamp in 4..6 {
+            assert!(!reader.tick(timestamp * 5).unwrap());
+        }
+
+        assert_eq!(reader.buffer().overruns(), 2);
+        assert_eq!(
+            reader.pop(),
+            Some(Sample {
+                timestamp: 10,
+                centi: 20,
+            })
+        );
+        assert_eq!(reader.buffer().len(), 3);
+
+        let sim = reader.stop().unwrap().release();
+        assert_eq!(sim.register(Control::ADDRESS), 0b0100_1000);
+    }
+
+    #[test]
+    fn test_stream_reader_errors() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0111_1100])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0111_1100]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+        ];
+        let sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        // the sensor is handed back to retry
+        let (sensor, error) = StreamReader::<_, 4>::new(sensor, Speed::Hz200).unwrap_err();
+        assert_eq!(error, Error::I2c(ErrorKind::Other));
+        let reader = StreamReader::<_, 4>::new(sensor, Speed::Hz200).unwrap();
+
+        let (reader, error) = reader.stop().unwrap_err();
+        assert_eq!(error, Error::I2c(ErrorKind::Other));
+        reader.stop().unwrap().release().done();
+    }
+}
@@ -0,0 +1,265 @@
+//! Driver variant that encodes the operating mode in the type.
+//!
+//! Only the operations that make sense in a given mode are available, e.g. the
+//! temperature can only be read in [`Continuous`] mode or measured in
+//! [`OneShot`] mode. [`Sensor::configure`] consumes the sensor and returns it
+//! in the new state.
+
+use embedded_hal::{
+    delay::DelayNs,
+    i2c::{ErrorType, I2c},
+};
+
+use crate::{Error, Mode, Speed, Status};
+
+/// Sensor is powered down.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerDown;
+
+/// Sensor converts continuously at the given speed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Continuous(pub Speed);
+
+/// Sensor is powered down between single conversions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OneShot;
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::PowerDown {}
+    impl Sealed for super::Continuous {}
+    impl Sealed for super::OneShot {}
+}
+
+/// Operating mode of a [`Sensor`].
+pub trait State: private::Sealed {
+    /// Mode written to the sensor when entering this state.
+    fn mode(&self) -> Mode;
+}
+
+impl State for PowerDown {
+    fn mode(&self) -> Mode {
+        Mode::PowerDown
+    }
+}
+
+impl State for Continuous {
+    fn mode(&self) -> Mode {
+        Mode::Continuous(self.0)
+    }
+}
+
+impl State for OneShot {
+    fn mode(&self) -> Mode {
+        Mode::PowerDown
+    }
+}
+
+/// Sensor in its previous state `S` with the error of a failed
+/// [`Sensor::configure`]
+pub type TransitionError<I2C, S> = (Sensor<I2C, S>, Error<<I2C as ErrorType>::Error>);
+
+/// WSEN-TIDS temperature sensor in operating mode `S`
+///
+/// Wraps a [`crate::Sensor`], which can be recovered with
+/// [`Sensor::into_inner`] for operations not available here.
+#[derive(Debug)]
+pub struct Sensor<I2C, S> {
+    sensor: crate::Sensor<I2C>,
+    state: S,
+}
+
+impl<I2C: I2c> Sensor<I2C, PowerDown> {
+    /// Creates a new sensor instance and powers the sensor down.
+    ///
+    /// Returns the untyped driver together with the error on failure.
+    pub fn new(
+        mut sensor: crate::Sensor<I2C>,
+    ) -> Result<Self, (crate::Sensor<I2C>, Error<I2C::Error>)> {
+        match sensor.configure(Mode::PowerDown) {
+            Ok(()) => Ok(Self {
+                sensor,
+                state: PowerDown,
+            }),
+            Err(e) => Err((sensor, e)),
+        }
+    }
+}
+
+impl<I2C: I2c, S: State> Sensor<I2C, S> {
+    /// Configure a new operating mode, returning the sensor in the new state.
+    ///
+    /// Returns the sensor in its previous state together with the error on
+    /// failure, so that the bus is not lost.
+    pub fn configure<T: State>(
+        mut self,
+        state: T,
+    ) -> Result<Sensor<I2C, T>, TransitionError<I2C, S>> {
+        match self.sensor.configure(state.mode()) {
+            Ok(()) => Ok(Sensor {
+                sensor: self.sensor,
+                state,
+            }),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Returns the untyped driver.
+    pub fn into_inner(self) -> crate::Sensor<I2C> {
+        self.sensor
+    }
+
+    /// Read device ID from the sensor.
+    pub fn read_device_id(&mut self) -> Result<u8, Error<I2C::Error>> {
+        self.sensor.read_device_id()
+    }
+
+    /// Read the status register.
+    pub fn read_status(&mut self) -> Result<Status, Error<I2C::Error>> {
+        self.sensor.read_status()
+    }
+
+    /// Disable high temperature limit interrupt generation.
+    pub fn disable_temperature_high_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sensor.disable_temperature_high_limit()
+    }
+
+    /// Disable low temperature limit interrupt generation.
+    pub fn disable_temperature_low_limit(&mut self) -> Result<(), Error<I2C::Error>> {
+        self.sensor.disable_temperature_low_limit()
+    }
+
+    /// Sets the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    pub fn temperature_high_limit_centi(&mut self, centi: i32) -> Result<(), Error<I2C::Error>> {
+        self.sensor.temperature_high_limit_centi(centi)
+    }
+
+    /// Sets the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    pub fn temperature_low_limit_centi(&mut self, centi: i32) -> Result<(), Error<I2C::Error>> {
+        self.sensor.temperature_low_limit_centi(centi)
+    }
+
+    /// Reads the temperature threshold high limit in hundredths of degrees
+    /// celcius.
+    pub fn read_temperature_high_limit_centi(&mut self) -> Result<Option<i32>, Error<I2C::Error>> {
+        self.sensor.read_temperature_high_limit_centi()
+    }
+
+    /// Reads the temperature threshold low limit in hundredths of degrees
+    /// celcius.
+    pub fn read_temperature_low_limit_centi(&mut self) -> Result<Option<i32>, Error<I2C::Error>> {
+        self.sensor.read_temperature_low_limit_centi()
+    }
+}
+
+impl<I2C: I2c> Sensor<I2C, Continuous> {
+    /// Configured conversion speed.
+    pub fn speed(&self) -> Speed {
+        self.state.0
+    }
+
+    /// Read the latest temperature in hundredths of degrees celcius.
+    pub fn read_temperature_centi(&mut self) -> Result<i16, Error<I2C::Error>> {
+        self.sensor.read_temperature_centi()
+    }
+
+    /// Read the latest temperature in degrees celcius.
+    #[cfg(feature = "float")]
+    pub fn read_temperature(&mut self) -> Result<f32, Error<I2C::Error>> {
+        self.sensor.read_temperature()
+    }
+}
+
+impl<I2C: I2c> Sensor<I2C, OneShot> {
+    /// Take a single measurement in hundredths of degrees celcius.
+    ///
+    /// See [`crate::Sensor::measure_once_centi`].
+    pub fn measure_centi<D: DelayNs>(&mut self, delay: &mut D) -> Result<i16, Error<I2C::Error>> {
+        self.sensor.measure_once_centi(delay)
+    }
+
+    /// Take a single measurement in degrees celcius.
+    #[cfg(feature = "float")]
+    pub fn measure<D: DelayNs>(&mut self, delay: &mut D) -> Result<f32, Error<I2C::Error>> {
+        self.sensor.measure_once(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        registers::{self, Control, Register, TempL},
+        AddressSelect,
+    };
+    use embedded_hal::i2c::ErrorKind;
+    use embedded_hal_mock::eh1::{
+        delay::NoopDelay,
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+    use std::vec;
+
+    #[test]
+    fn test_transitions() {
+        let expectations = [
+            // power down
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+            // continuous
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+            // one shot
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1001]),
+            I2cTransaction::write_read(0x38, vec![registers::Status::ADDRESS], vec![0b000]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0x18, 0xFC]),
+            // power down
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+        ];
+        let sensor = crate::Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        let sensor = Sensor::new(sensor).unwrap();
+
+        let mut sensor = sensor.configure(Continuous(Speed::Hz100)).unwrap();
+        assert_eq!(sensor.speed(), Speed::Hz100);
+        assert_eq!(sensor.read_temperature_centi().unwrap(), 2500);
+
+        let mut sensor = sensor.configure(OneShot).unwrap();
+        assert_eq!(sensor.measure_centi(&mut NoopDelay).unwrap(), -1000);
+
+        let sensor = sensor.configure(PowerDown).unwrap();
+
+        sensor.into_inner().release().done();
+    }
+
+    #[test]
+    fn test_failed_transition() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000])
+                .with_error(ErrorKind::Other),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0100_1000]),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100])
+                .with_error(ErrorKind::ArbitrationLoss),
+            I2cTransaction::write(0x38, vec![Control::ADDRESS, 0b0110_1100]),
+        ];
+        let sensor = crate::Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        // the sensor is handed back to retry
+        let (sensor, error) = Sensor::new(sensor).unwrap_err();
+        assert_eq!(error, Error::I2c(ErrorKind::Other));
+        let sensor = Sensor::new(sensor).unwrap();
+
+        let (sensor, error) = sensor.configure(Continuous(Speed::Hz100)).unwrap_err();
+        assert_eq!(error, Error::I2c(ErrorKind::ArbitrationLoss));
+        let sensor = sensor.configure(Continuous(Speed::Hz100)).unwrap();
+
+        sensor.into_inner().release().done();
+    }
+}
@@ -0,0 +1,171 @@
+//! Temperatures as `uom` quantities.
+//!
+//! Variants of the floating-point temperature methods of [`Sensor`] that take
+//! and return [`ThermodynamicTemperature`], so the unit is checked at compile
+//! time. Conversion from and to degrees celcius happens at the API boundary.
+
+use embedded_hal::{delay::DelayNs, i2c::I2c};
+use uom::si::{f32::ThermodynamicTemperature, thermodynamic_temperature::degree_celsius};
+
+use crate::{Error, Sensor};
+
+fn from_celcius(celcius: f32) -> ThermodynamicTemperature {
+    ThermodynamicTemperature::new::<degree_celsius>(celcius)
+}
+
+impl<I2C: I2c> Sensor<I2C> {
+    /// Sets the temperature threshold high limit.
+    ///
+    /// See [`Sensor::temperature_high_limit_centi`].
+    pub fn temperature_high_limit_quantity(
+        &mut self,
+        limit: ThermodynamicTemperature,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.temperature_high_limit(limit.get::<degree_celsius>())
+    }
+
+    /// Reads the temperature threshold high limit.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub fn read_temperature_high_limit_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<I2C::Error>> {
+        Ok(self.read_temperature_high_limit()?.map(from_celcius))
+    }
+
+    /// Sets the temperature threshold low limit.
+    ///
+    /// See [`Sensor::temperature_low_limit_centi`].
+    pub fn temperature_low_limit_quantity(
+        &mut self,
+        limit: ThermodynamicTemperature,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.temperature_low_limit(limit.get::<degree_celsius>())
+    }
+
+    /// Reads the temperature threshold low limit.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub fn read_temperature_low_limit_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<I2C::Error>> {
+        Ok(self.read_temperature_low_limit()?.map(from_celcius))
+    }
+
+    /// Read the temperature from the sensor.
+    pub fn read_temperature_quantity(
+        &mut self,
+    ) -> Result<ThermodynamicTemperature, Error<I2C::Error>> {
+        Ok(from_celcius(self.read_temperature()?))
+    }
+
+    /// Take a single temperature measurement.
+    ///
+    /// See [`Sensor::measure_once_centi`].
+    pub fn measure_once_quantity<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<ThermodynamicTemperature, Error<I2C::Error>> {
+        Ok(from_celcius(self.measure_once(delay)?))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: embedded_hal_async::i2c::I2c> crate::asynch::Sensor<I2C> {
+    /// Sets the temperature threshold high limit.
+    pub async fn temperature_high_limit_quantity(
+        &mut self,
+        limit: ThermodynamicTemperature,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.temperature_high_limit(limit.get::<degree_celsius>())
+            .await
+    }
+
+    /// Reads the temperature threshold high limit.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub async fn read_temperature_high_limit_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<I2C::Error>> {
+        Ok(self.read_temperature_high_limit().await?.map(from_celcius))
+    }
+
+    /// Sets the temperature threshold low limit.
+    pub async fn temperature_low_limit_quantity(
+        &mut self,
+        limit: ThermodynamicTemperature,
+    ) -> Result<(), Error<I2C::Error>> {
+        self.temperature_low_limit(limit.get::<degree_celsius>())
+            .await
+    }
+
+    /// Reads the temperature threshold low limit.
+    ///
+    /// Returns `None` if the limit is disabled.
+    pub async fn read_temperature_low_limit_quantity(
+        &mut self,
+    ) -> Result<Option<ThermodynamicTemperature>, Error<I2C::Error>> {
+        Ok(self.read_temperature_low_limit().await?.map(from_celcius))
+    }
+
+    /// Read the temperature from the sensor.
+    pub async fn read_temperature_quantity(
+        &mut self,
+    ) -> Result<ThermodynamicTemperature, Error<I2C::Error>> {
+        Ok(from_celcius(self.read_temperature().await?))
+    }
+
+    /// Take a single temperature measurement.
+    pub async fn measure_once_quantity<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<ThermodynamicTemperature, Error<I2C::Error>> {
+        Ok(from_celcius(self.measure_once(delay).await?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        registers::{Register, TempHighLimit, TempL, TempLowLimit},
+        AddressSelect,
+    };
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use std::vec;
+    use uom::si::thermodynamic_temperature::{degree_fahrenheit, kelvin};
+
+    #[test]
+    fn test_quantities() {
+        let expectations = [
+            I2cTransaction::write(0x38, vec![TempHighLimit::ADDRESS, 125]),
+            I2cTransaction::write(0x38, vec![TempLowLimit::ADDRESS, 63]),
+            I2cTransaction::write_read(0x38, vec![TempHighLimit::ADDRESS], vec![125]),
+            I2cTransaction::write_read(0x38, vec![TempLowLimit::ADDRESS], vec![0]),
+            I2cTransaction::write_read(0x38, vec![TempL::ADDRESS], vec![0xC4, 0x09]),
+        ];
+        let mut sensor = Sensor::new(I2cMock::new(&expectations), AddressSelect::High);
+
+        // 40 °C
+        sensor
+            .temperature_high_limit_quantity(ThermodynamicTemperature::new::<degree_fahrenheit>(
+                104.0,
+            ))
+            .unwrap();
+        // 0 °C
+        sensor
+            .temperature_low_limit_quantity(ThermodynamicTemperature::new::<kelvin>(273.15))
+            .unwrap();
+
+        let high = sensor.read_temperature_high_limit_quantity().unwrap();
+        assert!((high.unwrap().get::<degree_celsius>() - 39.68).abs() < 0.001);
+        assert_eq!(sensor.read_temperature_low_limit_quantity().unwrap(), None);
+
+        let temperature = sensor.read_temperature_quantity().unwrap();
+        assert!((temperature.get::<kelvin>() - 298.15).abs() < 0.001);
+
+        sensor.release().done();
+    }
+}